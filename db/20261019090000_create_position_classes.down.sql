@@ -0,0 +1,2 @@
+DROP VIEW IF EXISTS bad_debt;
+DROP TABLE IF EXISTS position_classes;
@@ -0,0 +1,23 @@
+-- Classification history of watched positions, one row per change
+CREATE TABLE IF NOT EXISTS position_classes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    protocol TEXT NOT NULL,          -- e.g., 'Aave', 'Morpho'
+    market TEXT NOT NULL,            -- Aave pool or Morpho market id
+    borrower TEXT NOT NULL,
+    class TEXT NOT NULL,             -- healthy, profitable, unprofitable, bad_debt
+    collateral_value REAL NOT NULL,
+    debt_value REAL NOT NULL,
+    value_unit TEXT NOT NULL,        -- 'base' for the Aave oracle currency, loan token for Morpho
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_position_classes_position ON position_classes(protocol, market, borrower);
+CREATE INDEX IF NOT EXISTS idx_position_classes_timestamp ON position_classes(timestamp);
+
+-- Positions whose latest classification is bad debt
+CREATE VIEW IF NOT EXISTS bad_debt AS
+SELECT protocol, market, borrower, collateral_value, debt_value,
+       debt_value - collateral_value AS shortfall, value_unit, timestamp
+FROM position_classes
+WHERE id IN (SELECT MAX(id) FROM position_classes GROUP BY protocol, market, borrower)
+  AND class = 'bad_debt';
@@ -0,0 +1,16 @@
+DROP VIEW IF EXISTS bad_debt;
+
+DROP INDEX IF EXISTS idx_liquidations_chain;
+DROP INDEX IF EXISTS idx_distributions_chain;
+DROP INDEX IF EXISTS idx_position_classes_chain;
+
+ALTER TABLE liquidations DROP COLUMN chain_id;
+ALTER TABLE distributions DROP COLUMN chain_id;
+ALTER TABLE position_classes DROP COLUMN chain_id;
+
+CREATE VIEW IF NOT EXISTS bad_debt AS
+SELECT protocol, market, borrower, collateral_value, debt_value,
+       debt_value - collateral_value AS shortfall, value_unit, timestamp
+FROM position_classes
+WHERE id IN (SELECT MAX(id) FROM position_classes GROUP BY protocol, market, borrower)
+  AND class = 'bad_debt';
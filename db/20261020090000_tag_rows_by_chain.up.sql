@@ -0,0 +1,17 @@
+-- Rows written before multi-chain support all come from Polygon
+ALTER TABLE liquidations ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 137;
+ALTER TABLE distributions ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 137;
+ALTER TABLE position_classes ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 137;
+
+CREATE INDEX IF NOT EXISTS idx_liquidations_chain ON liquidations(chain_id);
+CREATE INDEX IF NOT EXISTS idx_distributions_chain ON distributions(chain_id);
+CREATE INDEX IF NOT EXISTS idx_position_classes_chain ON position_classes(chain_id);
+
+-- Positions whose latest classification is bad debt, per chain
+DROP VIEW IF EXISTS bad_debt;
+CREATE VIEW bad_debt AS
+SELECT chain_id, protocol, market, borrower, collateral_value, debt_value,
+       debt_value - collateral_value AS shortfall, value_unit, timestamp
+FROM position_classes
+WHERE id IN (SELECT MAX(id) FROM position_classes GROUP BY chain_id, protocol, market, borrower)
+  AND class = 'bad_debt';
@@ -1,11 +1,20 @@
-use std::{collections::{HashMap, HashSet}, sync::Arc};
+use std::sync::Arc;
+use anyhow::anyhow;
 use ethers::{providers::Middleware, signers::{LocalWallet, Signer}, types::Address};
 
-use crate::{common::{self, Config}, constants};
+use crate::{chain_profile::ChainProfile, common::Config, constants};
+
+use super::{
+    abi_bindings::{IAaveV3Pool, IPoolAddressesProvider},
+    helpers,
+    reserve_registry::ReserveRegistry,
+    types::{AavePoolSpec, LiquidationRules},
+};
 
 
 #[derive(Debug, Clone)]
 pub struct AaveConfig {
+    pub name: String,
     pub wallet: LocalWallet,
     pub lending_pool: Address,
     pub aave_oracle: Address,
@@ -13,63 +22,102 @@ pub struct AaveConfig {
     pub rpc_url: String,
     pub ui_pool_data: Address,
     pub pool_address_provider: Address,
-    pub reserves: HashSet<Address>,
-    pub vdebt_tokens: HashMap<Address, Address>,
-    pub chain_id: u64,
-    
-    
-
+    pub reserves: Arc<ReserveRegistry>,
+    pub liquidation_rules: LiquidationRules,
+    pub chain: Arc<ChainProfile>,
+    pub deploy_block: u64,
 }
 
 impl Config for  AaveConfig  {
-     fn load() -> anyhow::Result<Self> {
-       
-        Ok(AaveConfig { 
-             wallet: constants::WALLET.clone(),
-             lending_pool: *constants::AAVE_V3_POOL, 
-             aave_oracle: *constants::AAVE_ORACLE, 
-             flash_liquidator: *constants::FLASH_LIQUIDATOR, 
-             rpc_url: constants::RPC_URL.to_string(), 
-             ui_pool_data: *constants::UIPOOL_DATA_PROVIDER, 
-             pool_address_provider: *constants::POOL_ADDRESS_PROVIDER,
-             reserves: constants::AAVE_RESERVES.clone(),
-             vdebt_tokens: HashMap::new(),
-             chain_id: constants::CHAIN_ID,
-            
+     fn load(chain: Arc<ChainProfile>) -> anyhow::Result<Self> {
+        let deployment = chain
+            .aave
+            .clone()
+            .ok_or_else(|| anyhow!("Aave is not deployed on {}", chain.name))?;
+
+        Ok(AaveConfig {
+             name: constants::AAVE_DEFAULT_POOL.to_string(),
+             wallet: chain.wallet(),
+             lending_pool: deployment.pool,
+             aave_oracle: deployment.oracle,
+             flash_liquidator: chain.flash_liquidator,
+             rpc_url: chain.rpc_url.clone(),
+             ui_pool_data: deployment.ui_pool_data,
+             pool_address_provider: deployment.pool_address_provider,
+             reserves: Arc::new(ReserveRegistry::new(
+                deployment.reserve_allowlist.clone(),
+                deployment.reserve_denylist.clone(),
+             )),
+             liquidation_rules: LiquidationRules::default(),
+             deploy_block: deployment.deploy_block,
+             chain,
+
         })
     }
 
     fn chain_id(&self) -> u64 {
-        self.chain_id
+        self.chain.chain_id
     }
 
     fn keeper_address(&self) -> Address {
         self.wallet.address()
-        
+
     }
- 
+
 }
 
 impl AaveConfig {
-       pub async fn populate_vdebt_tokens<M: Middleware + 'static>(
-        &mut self, 
+    /// Config for an extra pool, with the pool and oracle resolved from
+    /// its addresses provider
+    pub async fn for_pool<M: Middleware + 'static>(
+        chain: Arc<ChainProfile>,
+        spec: &AavePoolSpec,
         client: Arc<M>
-    ) -> anyhow::Result<()> {
-        let mut vdebt_mapping = HashMap::new();
-        
-
-        for reserve in &self.reserves {
-            let pool = common::fetch_contracts(client.clone())?.aave;
-            let data = pool.get_reserve_data(*reserve).call().await?;
-            let vdebt_token = data.variable_debt_token_address;
-        
-            vdebt_mapping.insert(*reserve, vdebt_token);
-        }
-        self.vdebt_tokens = vdebt_mapping;
-    
+    ) -> anyhow::Result<Self> {
+        let provider = IPoolAddressesProvider::new(spec.pool_address_provider, client);
+        let pool_call = provider.get_pool();
+        let oracle_call = provider.get_price_oracle();
+        let (lending_pool, aave_oracle) = tokio::try_join!(pool_call.call(), oracle_call.call())?;
 
-        Ok(())
+        Ok(AaveConfig {
+            name: spec.name.clone(),
+            lending_pool,
+            aave_oracle,
+            flash_liquidator: spec.flash_liquidator,
+            ui_pool_data: spec.ui_pool_data,
+            pool_address_provider: spec.pool_address_provider,
+            deploy_block: spec.deploy_block,
+            reserves: Arc::new(ReserveRegistry::new(
+                spec.reserve_allowlist.clone(),
+                spec.reserve_denylist.clone(),
+            )),
+            ..Self::load(chain)?
+        })
     }
-}
 
+    /// Prefix of the pool's sled trees; the default pool keeps the
+    /// original `aave:` names
+    pub fn namespace(&self) -> String {
+        if self.name == constants::AAVE_DEFAULT_POOL {
+            self.name.clone()
+        } else {
+            format!("aave:{}", self.name)
+        }
+    }
 
+    pub async fn populate_reserves<M: Middleware + 'static>(
+        &self,
+        client: Arc<M>
+    ) -> anyhow::Result<()> {
+        let pool = IAaveV3Pool::new(self.lending_pool, client);
+        self.reserves.load(&pool).await
+    }
+
+    pub async fn load_liquidation_rules<M: Middleware + 'static>(
+        &mut self,
+        client: Arc<M>
+    ) {
+        let pool = IAaveV3Pool::new(self.lending_pool, client);
+        self.liquidation_rules = helpers::liquidation_rules(&pool).await;
+    }
+}
@@ -4,31 +4,47 @@ use super::{abi_bindings::{
     IAaveV3Pool,  
     UiPoolDataProvider
 }, helpers::{
-    select_best_collateral, 
-    compute_debt_to_cover,
-}, types::LiquidationCandidate, 
+    a_token_allowed,
+    load_positions,
+    rank_pairs,
+    route_allowed,
+    user_emode_category,
+}, types::{AaveSkipReason, CollateralPosition, DebtPosition, LiquidationCandidate, LiquidationContext, PairEstimate}, 
 aave_config::AaveConfig, 
-aave_watchlist::AaveWatchList};
+aave_watchlist::AaveWatchList,
+atoken_inventory::ATokenInventory,
+position_book::PositionBook};
 
 use anyhow::ensure;
+use dashmap::DashMap;
 use ethers::{
-    providers::Middleware, types::{Address, U256}
+    providers::Middleware, types::{Address, Bytes, H256, U256}
 };
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::{Arc, atomic::{AtomicU64, Ordering}}};
 
-use crate::common::{
-    self, Liquidator, SwapQueryParams, abi_bindings::{IFlashLiquidator, LiquidationParams}, create_simulation_sandbox, execute_liq_tx, get_token_decimals, paraswap::ParaSwapClient, simulate_liq_tx};
+use crate::{common::{
+    Config, Liquidator, SwapQueryParams, abi_bindings::{IERC20, IFlashLiquidator, LiquidationParams}, create_simulation_sandbox, ensure_mined, execute_liq_tx, paraswap::ParaSwapClient, resolve_price_aggregator, simulate_liq_tx}, constants::AAVE_INVENTORY_UNWIND_INTERVAL, position_classifier::{PositionClass, PositionClassifier, PositionSnapshot}};
 use futures_util::{self, StreamExt, stream}; 
 
+const AAVE: &str = "Aave";
+
+/// Ranked pairs quoted on ParaSwap per borrower
+const MAX_PAIR_QUOTES: usize = 3;
+
 pub struct AaveLiquidator<M: Middleware + 'static> {
     pub lending_pool: IAaveV3Pool<M>,
     pub flash_liquidator: IFlashLiquidator<M>,
     pub aave_oracle: AaveOracle<M>,
     pub user_data: UiPoolDataProvider<M>,
-    pub client: Arc<M>,
     pub watch_list: Arc<AaveWatchList>,
-    pub config: Arc<AaveConfig>
+    pub book: Arc<PositionBook>,
+    pub inventory: Arc<ATokenInventory>,
+    pub config: Arc<AaveConfig>,
+    pub classifier: Arc<PositionClassifier>,
+    /// reserve → Chainlink aggregator behind its oracle source
+    price_feeds: DashMap<Address, Option<Address>>,
+    last_unwind: AtomicU64,
 }
 
 impl<M: Middleware> AaveLiquidator<M> {
@@ -36,21 +52,16 @@ impl<M: Middleware> AaveLiquidator<M> {
      pub fn new(
         config: Arc<AaveConfig>, 
         client: Arc<M>, 
-        watch_list: Arc<AaveWatchList>
+        watch_list: Arc<AaveWatchList>,
+        book: Arc<PositionBook>,
+        inventory: Arc<ATokenInventory>,
+        classifier: Arc<PositionClassifier>,
     ) -> Self {
 
-        let contracts = match common::fetch_contracts(client.clone()) {
-            Ok(c) => c,
-            Err(e) => {
-                tracing::error!("Failed to fetch contracts: {:?}", e);
-                panic!("Cannot initialize AaveLiquidator without contracts");
-            },
-        };
-
-        let lending_pool = contracts.aave;
-        let flash_liquidator = contracts.flash_liq;
-        let aave_oracle = contracts.aave_oracle;
-        let ui_pool_data = contracts.ui_pool_data_provider;
+        let lending_pool = IAaveV3Pool::new(config.lending_pool, client.clone());
+        let flash_liquidator = IFlashLiquidator::new(config.flash_liquidator, client.clone());
+        let aave_oracle = AaveOracle::new(config.aave_oracle, client.clone());
+        let ui_pool_data = UiPoolDataProvider::new(config.ui_pool_data, client);
         
         
         Self { 
@@ -58,10 +69,13 @@ impl<M: Middleware> AaveLiquidator<M> {
             flash_liquidator, 
             aave_oracle, 
             user_data: ui_pool_data,
-            client,
             watch_list,
-            config
-
+            book,
+            inventory,
+            config,
+            classifier,
+            price_feeds: DashMap::new(),
+            last_unwind: AtomicU64::new(0),
          }
     }
     async fn generate_liquidations(&self) -> anyhow::Result<Vec<LiquidationCandidate>> {
@@ -70,18 +84,96 @@ impl<M: Middleware> AaveLiquidator<M> {
             tracing::info!("Aave Liquidator: No borrowers to check");
             return Ok(vec![]);
         }
-        tracing::info!("Aave Liquidator: Checking {} borrowers", snapshot.len());
 
-    
-         let results: Vec<_> = stream::iter(snapshot)
-        .map(|(borrower, reserve)| async move {
-            self.analyze_borrower(borrower, reserve).await
+        // Entries are keyed by (borrower, reserve); pairs are chosen across
+        // all of a borrower's reserves, so analyze each borrower once
+        let borrowers: HashSet<Address> = snapshot
+            .into_iter()
+            .map(|(borrower, _)| borrower)
+            .collect();
+
+        // Health factors come from the local book, only borrowers near or
+        // below 1.0 are confirmed on-chain
+        self.book.sync(
+            &borrowers,
+            &self.lending_pool,
+            &self.user_data,
+            &self.aave_oracle,
+            &self.config
+        ).await?;
+
+        // Borrowers with fresh collateral-side activity go first and skip
+        // the local screen; the rest only if the book flags them
+        let dirty: Vec<Address> = self.watch_list
+            .take_dirty()
+            .into_iter()
+            .filter(|b| borrowers.contains(b))
+            .collect();
+
+        // Recently hopeless borrowers wait for the recheck interval unless dirty
+        let market = self.market();
+        let at_risk: Vec<Address> = dirty
+            .iter()
+            .copied()
+            .chain(
+                self.book
+                    .at_risk(&borrowers, &self.config)
+                    .into_iter()
+                    .filter(|b| !dirty.contains(b))
+                    .filter(|b| !self.classifier.is_hopeless(AAVE, market, *b))
+            )
+            .collect();
+
+        tracing::info!(
+            "Aave Liquidator: Checking {} of {} borrowers ({} dirty)",
+            at_risk.len(),
+            borrowers.len(),
+            dirty.len()
+        );
+
+        Ok(self.analyze_all(at_risk).await)
+    }
+
+    /// Re-check borrowers priced by updated feeds with fresh oracle prices.
+    /// Positions are left as the last cycle synced them.
+    async fn generate_for_feeds(&self, feeds: &[Address]) -> anyhow::Result<Vec<LiquidationCandidate>> {
+        let assets: HashSet<Address> = self.price_feeds
+            .iter()
+            .filter(|entry| entry.value().is_some_and(|f| feeds.contains(&f)))
+            .map(|entry| *entry.key())
+            .collect();
+
+        if assets.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let exposed = self.book.exposed_to(&assets, &self.config);
+        if exposed.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.book.refresh_prices(&self.aave_oracle, &self.config).await?;
+        let at_risk = self.book.at_risk(&exposed, &self.config);
+
+        tracing::info!(
+            "Aave Liquidator: Price update, checking {} of {} exposed borrowers",
+            at_risk.len(),
+            exposed.len()
+        );
+
+        Ok(self.analyze_all(at_risk).await)
+    }
+
+    async fn analyze_all(&self, borrowers: Vec<Address>) -> Vec<LiquidationCandidate> {
+        stream::iter(borrowers)
+        .map(|borrower| async move {
+            self.analyze_borrower(borrower).await
         })
         .buffer_unordered(10)
         .filter_map(|res| async {
             match res {
-                Ok(Some(candidate)) => Some(candidate),
-                Ok(None) => None,
+                Ok(Ok(candidate)) => Some(candidate),
+                Ok(Err(_)) => None,
                 Err(e) => {
                     tracing::warn!("analyze_borrower failed: {:?}", e);
                     None
@@ -89,95 +181,275 @@ impl<M: Middleware> AaveLiquidator<M> {
             }
         })
         .collect()
-        .await;
-
-        Ok(results)
+        .await
     }
 
     async fn analyze_borrower(
         &self,
-        borrower: Address, 
-        reserve: Address
-    ) -> anyhow::Result<Option<LiquidationCandidate>>{
+        borrower: Address,
+    ) -> anyhow::Result<Result<LiquidationCandidate, AaveSkipReason>>{
 
-        tracing::info!("Analyzing borrower {} for reserve {}", borrower, reserve);
-            
-        // 1. Health factor check
-        let (_, _, _, _, _, hf) = self.lending_pool.get_user_account_data(borrower).call().await?;
+        tracing::info!("Analyzing borrower {}", borrower);
 
+        let (total_collateral_base, total_debt_base, _, _, _, hf) =
+            self.lending_pool.get_user_account_data(borrower).call().await?;
+
+        let outcome = self.plan_borrower(borrower, total_debt_base, hf).await?;
+        if let Err(reason) = outcome {
+            tracing::debug!("⏭️ Skipping borrower {:?}: {}", borrower, reason);
+        }
+
+        let class = match outcome {
+            Err(AaveSkipReason::Healthy) => PositionClass::Healthy,
+            _ => PositionClass::liquidatable(outcome.is_ok(), total_collateral_base < total_debt_base),
+        };
+        let base_unit = 1e8;
+        self.classifier.record(PositionSnapshot {
+            protocol: AAVE,
+            market: self.market(),
+            borrower,
+            class,
+            collateral_value: total_collateral_base.low_u128() as f64 / base_unit,
+            debt_value: total_debt_base.low_u128() as f64 / base_unit,
+            value_unit: String::from("base"),
+        }).await;
+
+        Ok(outcome)
+    }
+
+    /// Classifier key of this pool
+    fn market(&self) -> H256 {
+        H256::from(self.config.lending_pool)
+    }
+
+    async fn plan_borrower(
+        &self,
+        borrower: Address,
+        total_debt_base: U256,
+        hf: U256,
+    ) -> anyhow::Result<Result<LiquidationCandidate, AaveSkipReason>>{
+
+        // 1. Health factor check
         if hf >= U256::exp10(18) {
             tracing::debug!("Borrower: {:?} is healthy with HF: {}", borrower, hf);
-            return Ok(None);
+            return Ok(Err(AaveSkipReason::Healthy));
         }
 
-         let v_debt = *self.config
-            .vdebt_tokens
-            .get(&reserve)
-            .ok_or_else(|| anyhow::anyhow!("Missing vDebt"))?;
+        // 2. E-mode overrides bonus, threshold and price source for its collateral
+        let emode = user_emode_category(borrower, &self.lending_pool, &self.config).await?;
 
-         let debt_to_cover = compute_debt_to_cover(
-            v_debt,
-            borrower,
-            hf,
-            self.client.clone()
-        ).await?;
-
-        // 3. Pick collateral
-        let collateral = select_best_collateral(
+        // 3. Every debt and collateral position the borrower holds
+        let (debts, collaterals) = load_positions(
             borrower,
+            emode.as_ref(),
             &self.lending_pool,
             &self.user_data,
             &self.aave_oracle,
-            reserve,
-            debt_to_cover,
-            self.client.clone(),
             &self.config
         ).await?;
 
-         // 4. ParaSwap routing
-        let (src_decimals, dest_decimals) = tokio::try_join!(
-            get_token_decimals(collateral.asset, self.client.clone()),
-            get_token_decimals(reserve, self.client.clone())
-        )?;
+        let isolated = matches!(collaterals.as_slice(), [c] if c.terms.flags.is_isolated());
+        let siloed = debts.iter().find(|d| d.flags.siloed_borrowing);
+        if isolated || siloed.is_some() {
+            tracing::debug!(
+                "Borrower {:?}: isolation mode {}, siloed debt {:?}, debts borrowable in isolation {}",
+                borrower,
+                isolated,
+                siloed.map(|d| d.asset),
+                debts.iter().all(|d| d.flags.borrowable_in_isolation)
+            );
+        }
+
+        // 4. Rank every debt x collateral pair on oracle prices, sized by
+        //    the pool's close factor and dust rules
+        let tuning = self.config.chain.tuning();
+        let ctx = LiquidationContext {
+            health_factor: hf,
+            total_debt_base,
+            rules: self.config.liquidation_rules,
+            swap_slippage_bps: tuning.aave_slippage_bps,
+            min_profit_bps: tuning.min_profit_bps,
+        };
+        let pairs = match rank_pairs(&debts, &collaterals, &ctx) {
+            Ok(pairs) => pairs,
+            Err(reason) => return Ok(Err(reason)),
+        };
+
+        // 5. Oracle ranking ignores route depth, so quote the leading pairs
+        //    and keep the one with the largest surplus after the swap
+        let paraswap_client = ParaSwapClient::new();
+        let mut best: Option<(U256, LiquidationCandidate)> = None;
+        let mut closest: Option<AaveSkipReason> = None;
+
+        for pair in pairs.iter().take(MAX_PAIR_QUOTES) {
+            let debt = debts.iter().find(|d| d.asset == pair.debt_asset);
+            let collateral = collaterals.iter().find(|c| c.asset == pair.collateral_asset);
+
+            let (Some(debt), Some(collateral)) = (debt, collateral) else {
+                continue;
+            };
+
+            match self.plan_pair(&paraswap_client, borrower, pair, debt, collateral).await {
+                Ok((surplus, candidate)) => {
+                    if best.as_ref().is_none_or(|(b, _)| surplus > *b) {
+                        best = Some((surplus, candidate));
+                    }
+                }
+                Err(e) => {
+                    closest = closest.max(e.downcast_ref::<AaveSkipReason>().copied());
+                    tracing::debug!(
+                        "Pair {:?}/{:?} rejected for {:?}: {:?}",
+                        pair.debt_asset,
+                        pair.collateral_asset,
+                        borrower,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(best
+            .map(|(_, candidate)| candidate)
+            .ok_or(closest.unwrap_or(AaveSkipReason::NotExecutable)))
+    }
+
+    /// Decide how to take the pair's collateral: as underlying sold through
+    /// ParaSwap, or as aTokens, whichever leaves the larger net surplus
+    async fn plan_pair(
+        &self,
+        paraswap_client: &ParaSwapClient,
+        borrower: Address,
+        pair: &PairEstimate,
+        debt: &DebtPosition,
+        collateral: &CollateralPosition,
+    ) -> anyhow::Result<(U256, LiquidationCandidate)> {
+
+        let liquidity = U256::from(
+            self.lending_pool.get_virtual_underlying_balance(collateral.asset).call().await?
+        );
+
+        let routed = match route_allowed(debt, &self.config.chain.tokens.profit_assets) {
+            Err(reason) => Err(anyhow::Error::new(reason)),
+            Ok(()) if liquidity >= pair.collateral_received => {
+                self.quote_pair(paraswap_client, borrower, pair, debt, collateral).await
+            }
+            Ok(()) => Err(anyhow::anyhow!("reserve {:?} lacks liquidity to redeem seized collateral", collateral.asset)),
+        };
+        let held = match a_token_allowed(collateral) {
+            Err(reason) => Err(anyhow::Error::new(reason)),
+            Ok(()) => self.a_token_candidate(borrower, pair, debt, collateral).await,
+        };
+
+        match (routed, held) {
+            (Ok(routed), Ok(held)) => Ok(if held.0 > routed.0 { held } else { routed }),
+            (Ok(routed), Err(e)) => {
+                tracing::debug!("No aToken path for {:?}: {:?}", collateral.asset, e);
+                Ok(routed)
+            }
+            (Err(e), Ok(held)) => {
+                tracing::debug!("No usable route for {:?}: {:?}", collateral.asset, e);
+                Ok(held)
+            }
+            // Prefer the skip reason, if either path has one
+            (Err(e), Err(f)) => Err(if f.is::<AaveSkipReason>() { f } else { e }),
+        }
+    }
+
+    /// Liquidation receiving aTokens, repaid from the keeper's own balance
+    /// of the debt asset. The surplus is the aTokens' oracle value, less
+    /// the unwind slippage unless they are kept as profit, over the repaid
+    /// debt, in the same base currency `quote_pair` reports.
+    async fn a_token_candidate(
+        &self,
+        borrower: Address,
+        pair: &PairEstimate,
+        debt: &DebtPosition,
+        collateral: &CollateralPosition,
+    ) -> anyhow::Result<(U256, LiquidationCandidate)> {
+
+        let inventory = IERC20::new(debt.asset, self.lending_pool.client())
+            .balance_of(self.config.keeper_address())
+            .call()
+            .await?;
+
+        ensure!(
+            inventory >= pair.debt_to_cover,
+            "keeper debt-asset balance too small for receiveAToken"
+        );
+
+        let mut received_base = pair.collateral_received * collateral.price
+            / U256::exp10(collateral.decimals as usize);
+        if !self.config.chain.tokens.profit_assets.contains(&collateral.asset) {
+            let unwind_bps = self.config.chain.tuning().atoken_unwind_slippage_bps;
+            received_base = received_base * U256::from(10_000 - unwind_bps) / U256::from(10_000u64);
+        }
+
+        let repaid_base = pair.debt_to_cover * debt.price / U256::exp10(debt.decimals as usize);
+        ensure!(received_base > repaid_base, "aTokens do not cover the repaid debt");
+
+        Ok((received_base - repaid_base, LiquidationCandidate {
+            debt_to_cover: pair.debt_to_cover,
+            debt_asset: debt.asset,
+            collateral_asset: pair.collateral_asset,
+            borrower,
+            swap_target: Address::zero(),
+            swap_proxy: Address::zero(),
+            swap_data: Bytes::default(),
+            min_amt_out: pair.collateral_received,
+            receive_a_token: true,
+        }))
+    }
+
+    /// Route the pair's collateral through ParaSwap, returns the surplus
+    /// over the repaid debt in the oracle base currency
+    async fn quote_pair(
+        &self,
+        paraswap_client: &ParaSwapClient,
+        borrower: Address,
+        pair: &PairEstimate,
+        debt: &DebtPosition,
+        collateral: &CollateralPosition,
+    ) -> anyhow::Result<(U256, LiquidationCandidate)> {
 
         let swap_params = SwapQueryParams {
             src_token: collateral.asset.to_string(),
-            dest_token: reserve.to_string(),
-            src_decimals,
-            dest_decimals,
-            amount: collateral.seize_amount.to_string(),
+            dest_token: debt.asset.to_string(),
+            src_decimals: collateral.decimals,
+            dest_decimals: debt.decimals,
+            amount: pair.collateral_received.to_string(),
             side: String::from("SELL"),
-            chain_id: self.config.chain_id,
-            slippage_bps: 30, // 0.3%
+            chain_id: self.config.chain.chain_id,
+            slippage_bps: self.config.chain.tuning().aave_slippage_bps,
             user_address: self.flash_liquidator.address().to_string(),
             receiver: self.flash_liquidator.address().to_string()
         };
 
-        let paraswap_client = ParaSwapClient::new();
         let route = paraswap_client.compose_swap_data(swap_params).await?;
 
         ensure!(
-            collateral.seize_amount >= route.src_amount, 
+            pair.collateral_received >= route.src_amount, 
             "swap src exceeds seized collateral"
         );
 
         ensure!(
-            route.min_amt_out >= debt_to_cover,
+            route.min_amt_out >= pair.debt_to_cover,
             "swap output insufficient to repay debt"
         );
 
-        Ok(Some(LiquidationCandidate { 
-            debt_to_cover, 
-            debt_asset: reserve, 
+        let surplus = (route.min_amt_out - pair.debt_to_cover) * debt.price
+            / U256::exp10(debt.decimals as usize);
+
+        Ok((surplus, LiquidationCandidate { 
+            debt_to_cover: pair.debt_to_cover, 
+            debt_asset: debt.asset, 
             collateral_asset: collateral.asset, 
             borrower, 
             swap_target: route.swap_target, 
             swap_proxy: route.token_transfer_proxy, 
             swap_data: route.swap_data,
-            min_amt_out: route.min_amt_out
-
+            min_amt_out: route.min_amt_out,
+            receive_a_token: false,
         }))
-        
     }
 }
 
@@ -188,23 +460,76 @@ where
     M: Middleware + 'static,
 {
     async fn run(&self, block_number: u64) -> anyhow::Result<()> {
-        tracing::info!("🚀 Running Aave liquidation engine for block {}", block_number);
+        tracing::info!("🚀 Running Aave liquidation engine [{}] for block {}", self.config.name, block_number);
         let candidates = self.generate_liquidations().await?;
         if candidates.is_empty() {
             tracing::info!("Aave Liquidator: No unhealthy borrowers to check");
+        } else {
+            self.execute_candidates(candidates, block_number).await?;
+        }
+
+        if block_number >= self.last_unwind.load(Ordering::Relaxed) + AAVE_INVENTORY_UNWIND_INTERVAL {
+            self.last_unwind.store(block_number, Ordering::Relaxed);
+
+            if let Err(e) = self.inventory.unwind(&self.lending_pool, &self.config).await {
+                tracing::error!("aToken inventory unwind failed: {:?}", e);
+            }
+        }
+
+        tracing::info!("Aave liquidation cycle completed for block {}", block_number);
+        Ok(())
+    }
+
+    async fn price_feeds(&self) -> anyhow::Result<Vec<Address>> {
+        for asset in self.config.reserves.enabled() {
+            if self.price_feeds.contains_key(&asset) {
+                continue;
+            }
+
+            let source = self.aave_oracle.get_source_of_asset(asset).call().await?;
+            let aggregator = resolve_price_aggregator(source, self.lending_pool.client()).await;
+            self.price_feeds.insert(asset, aggregator);
+        }
+
+        Ok(self.price_feeds.iter().filter_map(|entry| *entry.value()).collect())
+    }
+
+    async fn run_for_feeds(&self, block_number: u64, feeds: &[Address]) -> anyhow::Result<()> {
+        let candidates = self.generate_for_feeds(feeds).await?;
+        if !candidates.is_empty() {
+            self.execute_candidates(candidates, block_number).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<M: Middleware + 'static> AaveLiquidator<M> {
+    async fn execute_candidates(
+        &self,
+        candidates: Vec<LiquidationCandidate>,
+        block_number: u64
+    ) -> anyhow::Result<()> {
+
+        let (held, routed): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|c| c.receive_a_token);
+
+        for candidate in &held {
+            if let Err(e) = self.execute_a_token(candidate).await {
+                tracing::error!("receiveAToken liquidation of {:?} failed: {:?}", candidate.borrower, e);
+            }
+        }
+
+        if routed.is_empty() {
             return Ok(());
         }
 
-        let jobs = candidates
+        let jobs = routed
             .into_iter()
-            .map(|c| {
-                let debt = c.debt_to_cover;
-                let data = LiquidationParams::from(c);
-                (debt, data)
-            })
+            .map(|c| (c.debt_to_cover, LiquidationParams::from(c)))
             .collect::<Vec<_>>();
 
-        let sim_sandbox = create_simulation_sandbox(block_number, &self.flash_liquidator).await?;
+        let sim_sandbox = create_simulation_sandbox(&self.config.chain, block_number, &self.flash_liquidator).await?;
         let snapshot_id = sim_sandbox.snapshot().await?;
 
         for (loan_amt, liq_params) in &jobs {
@@ -235,7 +560,56 @@ where
                 }
             }
         }
-        tracing::info!("Aave liquidation cycle completed for block {}", block_number);
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// `liquidationCall(..., receiveAToken = true)` sent by the keeper,
+    /// checked with an `eth_call` first so a revert costs no gas
+    async fn execute_a_token(&self, candidate: &LiquidationCandidate) -> anyhow::Result<()> {
+        let pool = self.lending_pool.address();
+        let debt_token = IERC20::new(candidate.debt_asset, self.lending_pool.client());
+
+        let allowance = debt_token
+            .allowance(self.config.keeper_address(), pool)
+            .call()
+            .await?;
+        if allowance < candidate.debt_to_cover {
+            let receipt = debt_token.approve(pool, candidate.debt_to_cover).send().await?.await?;
+            ensure_mined(receipt, "debt token approval")?;
+        }
+
+        let call = self.lending_pool.liquidation_call(
+            candidate.collateral_asset,
+            candidate.debt_asset,
+            candidate.borrower,
+            candidate.debt_to_cover,
+            true,
+        );
+        call.call()
+            .await
+            .map_err(|e| anyhow::anyhow!("Simulation Reverted: {:?}", e))?;
+
+        let receipt = ensure_mined(call.send().await?.await?, "liquidationCall")?;
+        tracing::info!(
+            "📦 Liquidated {:?} for ~{} aTokens of {:?} (tx {:?})",
+            candidate.borrower,
+            candidate.min_amt_out,
+            candidate.collateral_asset,
+            receipt.transaction_hash
+        );
+
+        self.record_held(candidate.collateral_asset).await;
+        Ok(())
+    }
+
+    async fn record_held(&self, asset: Address) {
+        let a_token = match self.config.reserves.a_token(&asset) {
+            Some(a_token) => a_token,
+            None => return,
+        };
+
+        if let Err(e) = self.inventory.record(asset, a_token).await {
+            tracing::error!("Failed to record aToken inventory for {:?}: {:?}", asset, e);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use ethers::{core::rand, types::Address};
 use sled::{Db, Tree};
 use async_trait::async_trait;
@@ -12,11 +12,13 @@ use crate::common::WatchList;
 pub struct AaveWatchList {
     db: Arc<Tree>,
     cache: Arc<DashMap<Address, HashSet<Address>>>,
+    /// Borrowers whose position changed since the last executor cycle
+    dirty: DashSet<Address>,
 }
 
 impl AaveWatchList {
-    pub fn new(db: Arc<Db>) -> anyhow::Result<Self> {
-        let tree = db.open_tree("aave:watchlist")?;
+    pub fn new(db: Arc<Db>, namespace: &str) -> anyhow::Result<Self> {
+        let tree = db.open_tree(format!("{}:watchlist", namespace))?;
         let cache = Arc::new(DashMap::new());
 
         // Load all Sled rows into memory
@@ -30,6 +32,7 @@ impl AaveWatchList {
         Ok(Self {
             db: Arc::new(tree),
             cache,
+            dirty: DashSet::new(),
         })
     }
 
@@ -70,6 +73,22 @@ impl AaveWatchList {
         Ok(())
     }
 
+    /// Flag a watched borrower for a priority re-check
+    pub fn mark_dirty(&self, borrower: Address) {
+        if self.cache.contains_key(&borrower) {
+            self.dirty.insert(borrower);
+        }
+    }
+
+    /// Drain the borrowers flagged since the last call
+    pub fn take_dirty(&self) -> HashSet<Address> {
+        let drained: HashSet<Address> = self.dirty.iter().map(|b| *b).collect();
+        for borrower in drained.iter() {
+            self.dirty.remove(borrower);
+        }
+        drained
+    }
+
     pub fn contains(&self, borrower: Address, reserve: Address) -> bool {
         self.cache
             .get(&borrower)
@@ -17,5 +17,33 @@ abigen!(
     AaveOracle,
     r#"[
         function getAssetPrice(address asset) external view returns (uint256)
+        function getAssetsPrices(address[] calldata assets) external view returns (uint256[] memory)
+        function getSourceOfAsset(address asset) external view returns (address)
     ]"#
 );
+
+abigen!(
+    IPoolAddressesProvider,
+    r#"[
+        function getPoolConfigurator() external view returns (address)
+        function getPool() external view returns (address)
+        function getPriceOracle() external view returns (address)
+    ]"#
+);
+
+abigen!(
+    ILiquidationLogic,
+    r#"[
+        function CLOSE_FACTOR_HF_THRESHOLD() external view returns (uint256)
+        function MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD() external view returns (uint256)
+        function MIN_LEFTOVER_BASE() external view returns (uint256)
+    ]"#
+);
+
+abigen!(
+    IPoolConfigurator,
+    r#"[
+        event ReserveInitialized(address indexed asset, address indexed aToken, address stableDebtToken, address variableDebtToken, address interestRateStrategyAddress)
+    ]"#,
+    event_derives(serde::Deserialize, serde::Serialize)
+);
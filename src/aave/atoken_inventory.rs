@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use ethers::{
+    core::rand,
+    providers::Middleware,
+    types::{Address, TransactionRequest, U256},
+};
+use sled::{Db, Tree};
+
+use super::{abi_bindings::IAaveV3Pool, aave_config::AaveConfig};
+
+use crate::{
+    common::{
+        abi_bindings::IERC20,
+        ensure_mined,
+        get_token_decimals,
+        paraswap::ParaSwapClient,
+        Config,
+        SwapQueryParams,
+    },
+};
+
+/// aTokens held by the keeper after `receiveAToken` liquidations
+///
+/// Maintains:
+///     underlying asset → aToken
+///
+/// Balances are always read from chain; the book only remembers which
+/// reserves to look at.
+pub struct ATokenInventory {
+    db: Arc<Tree>,
+    cache: Arc<DashMap<Address, Address>>,
+}
+
+impl ATokenInventory {
+    pub fn new(db: Arc<Db>, namespace: &str) -> anyhow::Result<Self> {
+        let tree = db.open_tree(format!("{}:atoken_inventory", namespace))?;
+        let cache = Arc::new(DashMap::new());
+
+        for item in tree.iter() {
+            let (k, v) = item?;
+            cache.insert(Address::from_slice(&k), Address::from_slice(&v));
+        }
+
+        Ok(Self {
+            db: Arc::new(tree),
+            cache,
+        })
+    }
+
+    pub fn snapshot(&self) -> Vec<(Address, Address)> {
+        self.cache
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    pub async fn record(&self, asset: Address, a_token: Address) -> anyhow::Result<()> {
+        if self.cache.insert(asset, a_token).is_none() {
+            tracing::info!("📦 Holding aTokens of {:?}", asset);
+        }
+        self.persist(asset).await
+    }
+
+    pub async fn remove(&self, asset: Address) -> anyhow::Result<()> {
+        self.cache.remove(&asset);
+        self.persist(asset).await
+    }
+
+    async fn persist(&self, asset: Address) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        let maybe_a_token = self.cache.get(&asset).map(|v| *v);
+
+        tokio::task::spawn_blocking(move || {
+            if let Some(a_token) = maybe_a_token {
+                db.insert(asset.as_bytes(), a_token.as_bytes())?;
+            } else {
+                db.remove(asset.as_bytes())?;
+            }
+
+            if rand::random::<u8>().is_multiple_of(32) {
+                db.flush()?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Redeem held aTokens and sell the underlying for USDC.
+    ///
+    /// aTokens of profit-distribution assets are kept as yield-bearing
+    /// profit. Others are withdrawn once the reserve has the liquidity and
+    /// swapped through ParaSwap.
+    pub async fn unwind<M: Middleware + 'static>(
+        &self,
+        pool: &IAaveV3Pool<M>,
+        config: &AaveConfig,
+    ) -> anyhow::Result<()> {
+        let client = pool.client();
+        let keeper = config.keeper_address();
+
+        for (asset, a_token) in self.snapshot() {
+            let held = IERC20::new(a_token, client.clone())
+                .balance_of(keeper)
+                .call()
+                .await?;
+
+            if held.is_zero() {
+                self.remove(asset).await?;
+                continue;
+            }
+
+            if config.chain.tokens.profit_assets.contains(&asset) {
+                tracing::debug!("Keeping {} aTokens of {:?} as yield", held, asset);
+                continue;
+            }
+
+            let liquidity = U256::from(pool.get_virtual_underlying_balance(asset).call().await?);
+            if liquidity < held {
+                tracing::info!("⏳ Not enough {:?} liquidity to redeem {} aTokens yet", asset, held);
+                continue;
+            }
+
+            if let Err(e) = self.unwind_one(asset, held, pool, config).await {
+                tracing::error!("Failed to unwind aTokens of {:?}: {:?}", asset, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn unwind_one<M: Middleware + 'static>(
+        &self,
+        asset: Address,
+        amount: U256,
+        pool: &IAaveV3Pool<M>,
+        config: &AaveConfig,
+    ) -> anyhow::Result<()> {
+        let client = pool.client();
+        let keeper = config.keeper_address();
+        let target = config.chain.tokens.usdc;
+
+        // Quote first so nothing moves when there is no route
+        let swap_params = SwapQueryParams {
+            src_token: asset.to_string(),
+            dest_token: target.to_string(),
+            src_decimals: get_token_decimals(asset, client.clone()).await?,
+            dest_decimals: get_token_decimals(target, client.clone()).await?,
+            amount: amount.to_string(),
+            side: String::from("SELL"),
+            chain_id: config.chain.chain_id,
+            slippage_bps: config.chain.tuning().atoken_unwind_slippage_bps,
+            user_address: keeper.to_string(),
+            receiver: keeper.to_string(),
+        };
+        let route = ParaSwapClient::new().compose_swap_data(swap_params).await?;
+
+        ensure_mined(pool.withdraw(asset, amount, keeper).send().await?.await?, "aToken withdrawal")?;
+
+        let receipt = IERC20::new(asset, client.clone())
+            .approve(route.token_transfer_proxy, route.src_amount)
+            .send()
+            .await?
+            .await?;
+        ensure_mined(receipt, "unwind approval")?;
+
+        let swap_tx = TransactionRequest::new()
+            .from(keeper)
+            .to(route.swap_target)
+            .data(route.swap_data);
+        let receipt = client
+            .send_transaction(swap_tx, None)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send swap tx: {:?}", e))?
+            .await?;
+        let receipt = ensure_mined(receipt, "unwind swap")?;
+
+        tracing::info!(
+            "🔓 Unwound {} aTokens of {:?} into USDC (tx {:?})",
+            amount,
+            asset,
+            receipt.transaction_hash
+        );
+
+        Ok(())
+    }
+}
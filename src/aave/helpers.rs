@@ -3,73 +3,308 @@ use ethers::{
     providers::Middleware,
     types::{Address, U256},
 };
-use std::sync::Arc;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use std::{cmp::Reverse, collections::HashMap};
 
 use super::{
-    abi_bindings::{AaveOracle, IAaveV3Pool, UiPoolDataProvider},
+    abi_bindings::{AaveOracle, IAaveV3Pool, ILiquidationLogic, UiPoolDataProvider},
     aave_config::AaveConfig,
-    types::CollateralCandidate,
+    types::{
+        AaveSkipReason, CollateralPosition, CollateralTerms, DebtPosition, EModeCategory,
+        LiquidationContext, LiquidationRules, PairEstimate, ReserveFlags,
+    },
 };
 
-use crate::{
-    common::{abi_bindings::IERC20, get_token_decimals},
-    constants::{ATOKENS_ADDR, HF_LIQUIDATION_THRESHOLD_BPS, WAD},
-};
+use crate::common::{abi_bindings::IERC20, get_token_decimals};
 
 const BPS: u128 = 10_000;
 
+/// Extra debt covered on full liquidations for interest accrued before
+/// execution; the pool only pulls what is owed
+const FULL_LIQUIDATION_BUFFER_BPS: u128 = 10;
+
+//
+// ─────────────────────────────────────────────────────────────
+// E-Mode
+// ─────────────────────────────────────────────────────────────
+//
+
+pub async fn user_emode_category<M: Middleware + 'static>(
+    borrower: Address,
+    pool: &IAaveV3Pool<M>,
+    config: &AaveConfig,
+) -> Result<Option<EModeCategory>> {
+    let id = pool.get_user_e_mode(borrower).call().await?.low_u32() as u8;
+
+    emode_category(id, pool, config).await
+}
+
+/// Resolve an e-mode category by id, `None` for the default category
+pub async fn emode_category<M: Middleware + 'static>(
+    id: u8,
+    pool: &IAaveV3Pool<M>,
+    config: &AaveConfig,
+) -> Result<Option<EModeCategory>> {
+    if id == 0 {
+        return Ok(None);
+    }
+
+    if let Some(category) = config.reserves.emode_category(id) {
+        return Ok(Some(category));
+    }
+
+    // v3.2+ splits the category into a collateral config and membership bitmaps
+    let collateral_config_call = pool.get_e_mode_category_collateral_config(id);
+    let bitmap_call = pool.get_e_mode_category_collateral_bitmap(id);
+
+    let category = match tokio::try_join!(
+        collateral_config_call.call(),
+        bitmap_call.call(),
+    ) {
+        Ok((collateral_config, bitmap)) => EModeCategory {
+            id,
+            liquidation_threshold_bps: collateral_config.liquidation_threshold,
+            liquidation_bonus_bps: collateral_config.liquidation_bonus,
+            collateral_bitmap: Some(bitmap),
+            price_source: Address::zero(),
+        },
+        Err(e) => {
+            tracing::debug!("E-mode collateral config unavailable, using legacy getter: {:?}", e);
+
+            let legacy = pool.get_e_mode_category_data(id).call().await?;
+            EModeCategory {
+                id,
+                liquidation_threshold_bps: legacy.liquidation_threshold,
+                liquidation_bonus_bps: legacy.liquidation_bonus,
+                collateral_bitmap: None,
+                price_source: legacy.price_source,
+            }
+        }
+    };
+
+    config.reserves.cache_emode_category(category);
+
+    Ok(Some(category))
+}
+
 //
 // ─────────────────────────────────────────────────────────────
 // Liquidation Bonus
 // ─────────────────────────────────────────────────────────────
 //
 
-pub async fn liquidation_bonus_bps<M: Middleware + 'static>(
+pub async fn collateral_terms<M: Middleware + 'static>(
     asset: Address,
+    emode: Option<&EModeCategory>,
     pool: &IAaveV3Pool<M>,
-) -> Result<u16> {
-    let config = pool.get_configuration(asset).call().await?;
+    config: &AaveConfig,
+) -> Result<CollateralTerms> {
+    let reserve_config = pool.get_configuration(asset).call().await?.data;
+
+    let category = match emode {
+        Some(category) => {
+            let reserve_id = match config.reserves.id(&asset) {
+                Some(id) => id,
+                None => config.reserves.fetch(pool, asset).await?.id,
+            };
+            Some(category).filter(|c| c.applies_to(reserve_id, reserve_config))
+        }
+        None => None,
+    };
+
+    Ok(terms_from_config(reserve_config, category))
+}
 
-    let raw_bonus = ((config.data >> 32) & U256::from(0xFFFF)).as_u32() as u16;
+/// Collateral terms of a reserve, taking threshold and bonus from `category`
+/// when the borrower's e-mode applies to it
+fn terms_from_config(reserve_config: U256, category: Option<&EModeCategory>) -> CollateralTerms {
+    let protocol_fee = ((reserve_config >> 152) & U256::from(0xFFFF)).as_u32() as u16;
+
+    let (raw_threshold, raw_bonus) = match category {
+        Some(category) => (
+            category.liquidation_threshold_bps,
+            category.liquidation_bonus_bps,
+        ),
+        None => (
+            ((reserve_config >> 16) & U256::from(0xFFFF)).as_u32() as u16,
+            ((reserve_config >> 32) & U256::from(0xFFFF)).as_u32() as u16,
+        ),
+    };
 
-    if raw_bonus <= 10_000 {
-        return Ok(0);
+    CollateralTerms {
+        liquidation_threshold_bps: raw_threshold,
+        liquidation_bonus_bps: raw_bonus.saturating_sub(10_000),
+        liquidation_protocol_fee_bps: protocol_fee,
+        in_emode: category.is_some(),
+        flags: ReserveFlags::from_config(reserve_config),
     }
+}
 
-    Ok(raw_bonus - 10_000)
+//
+// ─────────────────────────────────────────────────────────────
+// Liquidation Rules
+// ─────────────────────────────────────────────────────────────
+//
+
+/// Close factor and dust constants from the pool's `LiquidationLogic`,
+/// falling back to the v3.3 values if the library cannot be queried
+pub async fn liquidation_rules<M: Middleware + 'static>(pool: &IAaveV3Pool<M>) -> LiquidationRules {
+    let fetch = async {
+        let logic = ILiquidationLogic::new(pool.get_liquidation_logic().call().await?, pool.client());
+
+        let hf_threshold_call = logic.close_factor_hf_threshold();
+        let base_threshold_call = logic.min_base_max_close_factor_threshold();
+        let leftover_call = logic.min_leftover_base();
+
+        let (close_factor_hf_threshold, min_base_max_close_factor_threshold, min_leftover_base) = tokio::try_join!(
+            hf_threshold_call.call(),
+            base_threshold_call.call(),
+            leftover_call.call(),
+        )?;
+
+        Ok::<_, anyhow::Error>(LiquidationRules {
+            close_factor_hf_threshold,
+            min_base_max_close_factor_threshold,
+            min_leftover_base,
+            ..Default::default()
+        })
+    };
+
+    match fetch.await {
+        Ok(rules) => {
+            tracing::info!("Aave liquidation rules loaded: {:?}", rules);
+            rules
+        }
+        Err(e) => {
+            tracing::warn!("Failed to read LiquidationLogic constants, using v3.3 defaults: {:?}", e);
+            LiquidationRules::default()
+        }
+    }
 }
 
 //
 // ─────────────────────────────────────────────────────────────
-// Debt To Cover
+// Borrower Positions
 // ─────────────────────────────────────────────────────────────
 //
 
-pub async fn compute_debt_to_cover<M: Middleware + 'static>(
+/// Eligible debt and collateral positions of a borrower across every
+/// reserve, priced in the oracle base currency
+pub async fn load_positions<M: Middleware + 'static>(
     borrower: Address,
-    vdebt_token: Address,
-    health_factor: U256,
-    client: Arc<M>,
-) -> Result<U256> {
-    let debt = IERC20::new(vdebt_token, client)
-        .balance_of(borrower)
+    emode: Option<&EModeCategory>,
+    pool: &IAaveV3Pool<M>,
+    ui_provider: &UiPoolDataProvider<M>,
+    oracle: &AaveOracle<M>,
+    config: &AaveConfig,
+) -> Result<(Vec<DebtPosition>, Vec<CollateralPosition>)> {
+
+    let (reserves, _) = ui_provider
+        .get_user_reserves_data(config.pool_address_provider, borrower)
         .call()
         .await?;
 
-    if debt.is_zero() {
-        return Ok(U256::zero());
+    let eligible = reserves
+        .iter()
+        .filter(|r| config.reserves.contains(&r.underlying_asset));
+
+    let debt_assets: Vec<Address> = eligible
+        .clone()
+        .filter(|r| !r.scaled_variable_debt.is_zero())
+        .map(|r| r.underlying_asset)
+        .collect();
+
+    let collateral_assets: Vec<Address> = eligible
+        .filter(|r| r.usage_as_collateral_enabled_on_user && !r.scaled_a_token_balance.is_zero())
+        .map(|r| r.underlying_asset)
+        .collect();
+
+    if debt_assets.is_empty() || collateral_assets.is_empty() {
+        return Ok((vec![], vec![]));
     }
 
-    let threshold =
-        U256::from(HF_LIQUIDATION_THRESHOLD_BPS) * *WAD / U256::from(BPS);
+    // Legacy e-mode price source: the debt is always priced by it, the
+    // collateral only when it belongs to the borrower's category
+    let price_source = emode
+        .map(|c| c.price_source)
+        .filter(|src| !src.is_zero());
+
+    let mut sources: Vec<Address> = debt_assets
+        .iter()
+        .chain(collateral_assets.iter())
+        .copied()
+        .chain(price_source)
+        .collect();
+    sources.sort();
+    sources.dedup();
+
+    let quoted = oracle.get_assets_prices(sources.clone()).call().await?;
+    let prices: HashMap<Address, U256> = sources.into_iter().zip(quoted).collect();
+    let price_of = |asset: Address| prices.get(&asset).copied().unwrap_or_default();
+
+    let client = pool.client();
+
+    let debts: Vec<DebtPosition> = stream::iter(debt_assets)
+        .map(|asset| {
+            let client = client.clone();
+            async move {
+                let vdebt = config
+                    .reserves
+                    .vdebt_token(&asset)
+                    .ok_or_else(|| anyhow!("missing vDebt token"))?;
+
+                let balance = IERC20::new(vdebt, client.clone())
+                    .balance_of(borrower)
+                    .call()
+                    .await?;
+                let decimals = get_token_decimals(asset, client.clone()).await?;
+                let reserve_config = pool.get_configuration(asset).call().await?.data;
+
+                Ok::<_, anyhow::Error>(DebtPosition {
+                    asset,
+                    balance,
+                    price: price_of(price_source.unwrap_or(asset)),
+                    decimals,
+                    flags: ReserveFlags::from_config(reserve_config),
+                })
+            }
+        })
+        .buffer_unordered(4)
+        .try_collect()
+        .await?;
 
-    let close_factor = if health_factor < threshold {
-        BPS
-    } else {
-        BPS / 2
-    };
+    let collaterals: Vec<CollateralPosition> = stream::iter(collateral_assets)
+        .map(|asset| {
+            let client = client.clone();
+            async move {
+                let terms = collateral_terms(asset, emode, pool, config).await?;
+                let atoken = resolve_atoken(pool, config, asset).await?;
+
+                let balance = IERC20::new(atoken, client.clone())
+                    .balance_of(borrower)
+                    .call()
+                    .await?;
+                let decimals = get_token_decimals(asset, client.clone()).await?;
+
+                let source = match price_source {
+                    Some(src) if terms.in_emode => src,
+                    _ => asset,
+                };
+
+                Ok::<_, anyhow::Error>(CollateralPosition {
+                    asset,
+                    balance,
+                    price: price_of(source),
+                    decimals,
+                    terms,
+                })
+            }
+        })
+        .buffer_unordered(4)
+        .try_collect()
+        .await?;
 
-    Ok(debt * U256::from(close_factor) / U256::from(BPS))
+    Ok((debts, collaterals))
 }
 
 //
@@ -78,126 +313,295 @@ pub async fn compute_debt_to_cover<M: Middleware + 'static>(
 // ─────────────────────────────────────────────────────────────
 //
 
-pub async fn estimate_seizable_collateral<M: Middleware + 'static>(
+/// Collateral released for `debt_to_cover`, bonus included
+pub fn estimate_seizable_collateral(
     debt_to_cover: U256,
-    collateral_asset: Address,
-    debt_asset: Address,
-    liquidation_bonus_bps: u16,
-    oracle: &AaveOracle<M>,
-    client: Arc<M>,
-) -> Result<U256> {
-    if debt_to_cover.is_zero() {
-        return Ok(U256::zero());
-    }
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+) -> Option<U256> {
+    let bonus = U256::from(BPS) + U256::from(collateral.terms.liquidation_bonus_bps);
 
-    let collateral_price_call = oracle.get_asset_price(collateral_asset);
-    let debt_price_call = oracle.get_asset_price(debt_asset);
+    let numerator = debt_to_cover
+        .checked_mul(debt.price)?
+        .checked_mul(U256::exp10(collateral.decimals as usize))?
+        .checked_mul(bonus)?;
 
-    let (collateral_price, debt_price) = tokio::try_join!(
-        collateral_price_call.call(),
-        debt_price_call.call(),
-    )?;
+    let denominator = collateral
+        .price
+        .checked_mul(U256::exp10(debt.decimals as usize))?
+        .checked_mul(U256::from(BPS))?;
 
-    let (coll_decimals, debt_decimals) = tokio::try_join!(
-        get_token_decimals(collateral_asset, client.clone()),
-        get_token_decimals(debt_asset, client.clone()),
-    )?;
+    numerator.checked_div(denominator)
+}
 
-    let numerator = debt_to_cover
-        .checked_mul(debt_price)
-        .ok_or(anyhow!("overflow: debt * price"))?
-        .checked_mul(U256::exp10(coll_decimals as usize))
-        .ok_or(anyhow!("overflow: collateral decimals"))?
-        .checked_mul(U256::from(liquidation_bonus_bps))
-        .ok_or(anyhow!("overflow: bonus"))?;
+/// Debt repaid when `seize_amount` of collateral is seized
+fn debt_for_collateral(
+    seize_amount: U256,
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+) -> Option<U256> {
+    let bonus = U256::from(BPS) + U256::from(collateral.terms.liquidation_bonus_bps);
+
+    let numerator = seize_amount
+        .checked_mul(collateral.price)?
+        .checked_mul(U256::exp10(debt.decimals as usize))?
+        .checked_mul(U256::from(BPS))?;
+
+    let denominator = debt
+        .price
+        .checked_mul(U256::exp10(collateral.decimals as usize))?
+        .checked_mul(bonus)?;
+
+    numerator.checked_div(denominator)
+}
 
-    let denominator = collateral_price
-        .checked_mul(U256::from(BPS))
-        .ok_or(anyhow!("overflow: price * bps"))?
-        .checked_mul(U256::exp10(debt_decimals as usize))
-        .ok_or(anyhow!("overflow: debt decimals"))?;
+fn to_base(amount: U256, price: U256, decimals: u8) -> Option<U256> {
+    amount
+        .checked_mul(price)?
+        .checked_div(U256::exp10(decimals as usize))
+}
+
+/// Token amount worth at least `value` in the base currency
+fn from_base_up(value: U256, price: U256, decimals: u8) -> Option<U256> {
+    let numerator = value.checked_mul(U256::exp10(decimals as usize))?;
+    let amount = numerator.checked_div(price)?;
 
-    Ok(numerator / denominator)
+    if amount * price < numerator {
+        Some(amount + 1)
+    } else {
+        Some(amount)
+    }
 }
 
 //
 // ─────────────────────────────────────────────────────────────
-// Select Best Collateral
+// Liquidation Sizing
 // ─────────────────────────────────────────────────────────────
 //
 
-pub async fn select_best_collateral<M: Middleware + 'static>(
-    borrower: Address,
-    pool: &IAaveV3Pool<M>,
-    ui_provider: &UiPoolDataProvider<M>,
-    oracle: &AaveOracle<M>,
-    debt_asset: Address,
+/// Most debt of this reserve the pool lets one liquidation repay.
+///
+/// The whole reserve debt is liquidatable unless the health factor is above
+/// `CLOSE_FACTOR_HF_THRESHOLD` and both positions are worth at least
+/// `MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD`; then at most the default close
+/// factor of the borrower's total debt can be repaid.
+fn max_liquidatable_debt(
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+    ctx: &LiquidationContext,
+) -> Option<U256> {
+    let rules = &ctx.rules;
+
+    let debt_base = to_base(debt.balance, debt.price, debt.decimals)?;
+    let collateral_base = to_base(collateral.balance, collateral.price, collateral.decimals)?;
+
+    if collateral_base >= rules.min_base_max_close_factor_threshold
+        && debt_base >= rules.min_base_max_close_factor_threshold
+        && ctx.health_factor > rules.close_factor_hf_threshold
+    {
+        let default_liquidatable = ctx.total_debt_base
+            * U256::from(rules.default_close_factor_bps)
+            / U256::from(BPS);
+
+        if debt_base > default_liquidatable {
+            return default_liquidatable
+                .checked_mul(U256::exp10(debt.decimals as usize))?
+                .checked_div(debt.price);
+        }
+    }
+
+    Some(debt.balance)
+}
+
+/// Debt repaid and collateral seized (protocol fee included) when
+/// repaying up to `debt_limit` against at most `collateral_limit`
+fn size_liquidation(
+    debt_limit: U256,
+    collateral_limit: U256,
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+) -> Option<(U256, U256)> {
+    let max_seize = estimate_seizable_collateral(debt_limit, debt, collateral)?;
+
+    if max_seize > collateral_limit {
+        Some((debt_for_collateral(collateral_limit, debt, collateral)?, collateral_limit))
+    } else {
+        Some((debt_limit, max_seize))
+    }
+}
+
+/// Whether a partial liquidation leaves debt or collateral below
+/// `MIN_LEFTOVER_BASE`, which the pool rejects
+fn leaves_dust(
     debt_to_cover: U256,
-    client: Arc<M>,
-    config: &AaveConfig,
-) -> Result<CollateralCandidate> {
+    seize_amount: U256,
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+    ctx: &LiquidationContext,
+) -> Option<bool> {
+    if debt_to_cover >= debt.balance || seize_amount >= collateral.balance {
+        return Some(false);
+    }
 
-    let (reserves, _) = ui_provider
-        .get_user_reserves_data(config.pool_address_provider, borrower)
-        .call()
-        .await?;
+    let debt_left = to_base(debt.balance - debt_to_cover, debt.price, debt.decimals)?;
+    let collateral_left = to_base(collateral.balance - seize_amount, collateral.price, collateral.decimals)?;
 
-    let mut best: Option<CollateralCandidate> = None;
+    Some(debt_left < ctx.rules.min_leftover_base || collateral_left < ctx.rules.min_leftover_base)
+}
 
-    for reserve in reserves {
+//
+// ─────────────────────────────────────────────────────────────
+// Pair Selection
+// ─────────────────────────────────────────────────────────────
+//
 
-        if !reserve.usage_as_collateral_enabled_on_user
-            || reserve.scaled_a_token_balance.is_zero()
-        {
-            continue;
-        }
+/// Oracle-priced outcome of liquidating one debt/collateral pair.
+///
+/// Sizes the liquidation the way the pool does (close factor, collateral
+/// cap, dust rule), then deducts the protocol fee taken from the bonus and
+/// the expected swap slippage.
+pub fn evaluate_pair(
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+    ctx: &LiquidationContext,
+) -> Result<PairEstimate, AaveSkipReason> {
+    let collateral_flags = &collateral.terms.flags;
+
+    if !debt.flags.active || !collateral_flags.active {
+        return Err(AaveSkipReason::ReserveInactive);
+    }
+    if debt.flags.paused || collateral_flags.paused {
+        return Err(AaveSkipReason::ReservePaused);
+    }
+    if debt.asset == collateral.asset
+        || collateral.terms.liquidation_threshold_bps == 0
+    {
+        return Err(AaveSkipReason::CollateralNotSeizable);
+    }
+
+    size_pair(debt, collateral, ctx).ok_or(AaveSkipReason::DustLimit)?
+}
+
+/// `None` when sizing leaves nothing to liquidate
+fn size_pair(
+    debt: &DebtPosition,
+    collateral: &CollateralPosition,
+    ctx: &LiquidationContext,
+) -> Option<Result<PairEstimate, AaveSkipReason>> {
+    if debt.balance.is_zero() || collateral.balance.is_zero() {
+        return None;
+    }
+
+    let max_debt = max_liquidatable_debt(debt, collateral, ctx)?;
+    let (mut debt_to_cover, mut seize_amount) =
+        size_liquidation(max_debt, collateral.balance, debt, collateral)?;
+
+    // Shrink the liquidation so both sides keep at least the minimum leftover
+    if leaves_dust(debt_to_cover, seize_amount, debt, collateral, ctx)? {
+        let debt_room = debt.balance.checked_sub(
+            from_base_up(ctx.rules.min_leftover_base, debt.price, debt.decimals)?,
+        )?;
+        let collateral_room = collateral.balance.checked_sub(
+            from_base_up(ctx.rules.min_leftover_base, collateral.price, collateral.decimals)?,
+        )?;
+
+        (debt_to_cover, seize_amount) =
+            size_liquidation(max_debt.min(debt_room), collateral_room, debt, collateral)?;
+    }
 
-        let asset = reserve.underlying_asset;
+    if debt_to_cover.is_zero() || seize_amount.is_zero() {
+        return None;
+    }
 
-        let bonus = liquidation_bonus_bps(asset, pool).await?;
+    // Otherwise the interest accrued until execution is left behind as dust
+    if debt_to_cover == debt.balance {
+        debt_to_cover += debt.balance * U256::from(FULL_LIQUIDATION_BUFFER_BPS) / U256::from(BPS);
+    }
 
-        let atoken = resolve_atoken(pool, asset).await?;
+    let bonus = U256::from(BPS) + U256::from(collateral.terms.liquidation_bonus_bps);
+    let bonus_amount = seize_amount - seize_amount * U256::from(BPS) / bonus;
+    let protocol_fee = bonus_amount * U256::from(collateral.terms.liquidation_protocol_fee_bps)
+        / U256::from(BPS);
+    let collateral_received = seize_amount - protocol_fee;
 
-        let balance = IERC20::new(atoken, client.clone())
-            .balance_of(borrower)
-            .call()
-            .await?;
+    let received_base = to_base(collateral_received, collateral.price, collateral.decimals)?;
+    let repaid_base = to_base(debt_to_cover, debt.price, debt.decimals)?;
+    let swap_cost = received_base * U256::from(ctx.swap_slippage_bps) / U256::from(BPS);
 
-        let seize = estimate_seizable_collateral(
-            debt_to_cover,
-            asset,
-            debt_asset,
-            bonus,
-            oracle,
-            client.clone(),
-        )
-        .await?
-        .min(balance);
+    let expected_profit_base = received_base
+        .saturating_sub(repaid_base)
+        .saturating_sub(swap_cost);
 
-        if seize.is_zero() {
-            continue;
-        }
+    let min_profit_base = repaid_base * U256::from(ctx.min_profit_bps) / U256::from(BPS);
+    if expected_profit_base.is_zero() || expected_profit_base < min_profit_base {
+        return Some(Err(AaveSkipReason::Unprofitable));
+    }
 
-        let price = oracle.get_asset_price(asset).call().await?;
+    Some(Ok(PairEstimate {
+        debt_asset: debt.asset,
+        collateral_asset: collateral.asset,
+        debt_to_cover,
+        collateral_received,
+        expected_profit_base,
+    }))
+}
 
-        let usd_value = seize * price;
+/// Every profitable debt/collateral pair, most profitable first, or the
+/// reason closest to executable when there is none.
+pub fn rank_pairs(
+    debts: &[DebtPosition],
+    collaterals: &[CollateralPosition],
+    ctx: &LiquidationContext,
+) -> Result<Vec<PairEstimate>, AaveSkipReason> {
+    if debts.is_empty() || collaterals.is_empty() {
+        return Err(AaveSkipReason::NoPositions);
+    }
 
-        let candidate = CollateralCandidate {
-            asset,
-            liquidation_bonus_bps: bonus,
-            seize_amount: seize,
-            usd_value,
-        };
+    let mut pairs = vec![];
+    let mut closest: Option<AaveSkipReason> = None;
 
-        if best
-            .as_ref()
-            .map_or(true, |b| candidate.usd_value > b.usd_value)
-        {
-            best = Some(candidate);
+    for debt in debts {
+        for collateral in collaterals {
+            match evaluate_pair(debt, collateral, ctx) {
+                Ok(pair) => pairs.push(pair),
+                Err(reason) => closest = closest.max(Some(reason)),
+            }
         }
     }
 
-    best.ok_or_else(|| anyhow!("no viable collateral"))
+    if pairs.is_empty() {
+        return Err(closest.unwrap_or(AaveSkipReason::Unprofitable));
+    }
+
+    pairs.sort_by_key(|p| Reverse(p.expected_profit_base));
+    Ok(pairs)
+}
+
+//
+// ─────────────────────────────────────────────────────────────
+// Isolation Mode / Siloed Borrowing
+// ─────────────────────────────────────────────────────────────
+//
+
+/// Whether the pair's collateral may be taken as aTokens. The pool enables
+/// received aTokens as collateral for the keeper, except on reserves with
+/// a debt ceiling, so isolated aTokens could never back the inventory.
+pub fn a_token_allowed(collateral: &CollateralPosition) -> Result<(), AaveSkipReason> {
+    if collateral.terms.flags.is_isolated() {
+        return Err(AaveSkipReason::IsolatedATokens);
+    }
+    Ok(())
+}
+
+/// Whether the pair's collateral may be sold into its debt asset. The
+/// surplus of a routed liquidation stays in the flash liquidator in that
+/// asset, and siloed assets are only held when profits are distributed in
+/// them.
+pub fn route_allowed(debt: &DebtPosition, profit_assets: &[Address]) -> Result<(), AaveSkipReason> {
+    if debt.flags.siloed_borrowing && !profit_assets.contains(&debt.asset) {
+        return Err(AaveSkipReason::SiloedDebt);
+    }
+    Ok(())
 }
 
 //
@@ -208,18 +612,15 @@ pub async fn select_best_collateral<M: Middleware + 'static>(
 
 async fn resolve_atoken<M: Middleware + 'static>(
     pool: &IAaveV3Pool<M>,
+    config: &AaveConfig,
     asset: Address,
 ) -> Result<Address> {
 
-    if let Some(addr) = ATOKENS_ADDR.get(&asset) {
-        return Ok(*addr);
+    if let Some(addr) = config.reserves.a_token(&asset) {
+        return Ok(addr);
     }
 
-    let data = pool.get_reserve_data(asset).call().await?;
-
-    ATOKENS_ADDR.insert(asset, data.a_token_address);
-
-    Ok(data.a_token_address)
+    Ok(config.reserves.fetch(pool, asset).await?.a_token)
 }
 
 //
@@ -236,13 +637,286 @@ pub async fn has_outstanding_debt<M: Middleware + 'static>(
 ) -> Result<bool> {
 
     let vdebt = config
-        .vdebt_tokens
-        .get(&reserve)
+        .reserves
+        .vdebt_token(&reserve)
         .ok_or_else(|| anyhow!("missing vDebt token"))?;
 
-    let token = IERC20::new(*vdebt, pool.client());
+    let token = IERC20::new(vdebt, pool.client());
 
     let debt = token.balance_of(borrower).call().await?;
 
     Ok(!debt.is_zero())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIVE: ReserveFlags = ReserveFlags {
+        active: true,
+        paused: false,
+        borrowable_in_isolation: false,
+        siloed_borrowing: false,
+        debt_ceiling: U256::zero(),
+    };
+
+    fn wad_bps(bps: u64) -> U256 {
+        U256::from(bps) * U256::exp10(14)
+    }
+
+    /// USDC-like debt priced at 1.00 in the 8-decimal base currency
+    fn usdc(whole: u64) -> DebtPosition {
+        DebtPosition {
+            asset: Address::from_low_u64_be(1),
+            balance: U256::from(whole) * U256::exp10(6),
+            price: U256::exp10(8),
+            decimals: 6,
+            flags: ACTIVE,
+        }
+    }
+
+    /// WETH-like collateral at 2000.00 with a 5% bonus and a 10% protocol fee
+    fn weth(wei: u128) -> CollateralPosition {
+        CollateralPosition {
+            asset: Address::from_low_u64_be(2),
+            balance: U256::from(wei),
+            price: U256::from(2_000) * U256::exp10(8),
+            decimals: 18,
+            terms: CollateralTerms {
+                liquidation_threshold_bps: 8_250,
+                liquidation_bonus_bps: 500,
+                liquidation_protocol_fee_bps: 1_000,
+                in_emode: false,
+                flags: ACTIVE,
+            },
+        }
+    }
+
+    fn ctx(hf_bps: u64, total_debt_whole: u64) -> LiquidationContext {
+        LiquidationContext {
+            health_factor: wad_bps(hf_bps),
+            total_debt_base: U256::from(total_debt_whole) * U256::exp10(8),
+            rules: LiquidationRules::default(),
+            swap_slippage_bps: 30,
+            min_profit_bps: 0,
+        }
+    }
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn close_factor_applies_only_above_the_hf_threshold() {
+        let (debt, collateral) = (usdc(10_000), weth(10 * ETH));
+
+        // HF 0.96: half of the total debt
+        assert_eq!(max_liquidatable_debt(&debt, &collateral, &ctx(9_600, 10_000)), Some(usdc(5_000).balance));
+        // HF 0.95 is not above the threshold, HF 0.94 is below it: all of it
+        assert_eq!(max_liquidatable_debt(&debt, &collateral, &ctx(9_500, 10_000)), Some(debt.balance));
+        assert_eq!(max_liquidatable_debt(&debt, &collateral, &ctx(9_400, 10_000)), Some(debt.balance));
+    }
+
+    #[test]
+    fn close_factor_needs_both_positions_above_the_base_threshold() {
+        // 1,500 of debt is below MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD
+        let debt = usdc(1_500);
+        assert_eq!(max_liquidatable_debt(&debt, &weth(10 * ETH), &ctx(9_900, 1_500)), Some(debt.balance));
+
+        // 1,800 of collateral is below it too
+        let debt = usdc(5_000);
+        assert_eq!(max_liquidatable_debt(&debt, &weth(9 * ETH / 10), &ctx(9_900, 5_000)), Some(debt.balance));
+
+        // Exactly 2,000 on both sides is at the threshold
+        let debt = usdc(2_000);
+        assert_eq!(max_liquidatable_debt(&debt, &weth(ETH), &ctx(9_900, 2_000)), Some(usdc(1_000).balance));
+
+        // The close factor caps against the total debt, a smaller reserve debt is fully liquidatable
+        let debt = usdc(2_000);
+        assert_eq!(max_liquidatable_debt(&debt, &weth(10 * ETH), &ctx(9_900, 10_000)), Some(debt.balance));
+    }
+
+    #[test]
+    fn seized_collateral_is_capped_by_the_balance() {
+        let debt = usdc(10_000);
+
+        // 1,000 repaid * 1.05 / 2,000 = 0.525 WETH
+        assert_eq!(
+            size_liquidation(usdc(1_000).balance, U256::from(10 * ETH), &debt, &weth(10 * ETH)),
+            Some((usdc(1_000).balance, U256::from(525 * ETH / 1_000)))
+        );
+
+        // Only 0.3 WETH available: 0.3 * 2,000 / 1.05 repaid
+        assert_eq!(
+            size_liquidation(usdc(1_000).balance, U256::from(3 * ETH / 10), &debt, &weth(3 * ETH / 10)),
+            Some((U256::from(571_428_571u64), U256::from(3 * ETH / 10)))
+        );
+    }
+
+    #[test]
+    fn partial_liquidations_must_not_leave_dust() {
+        let (debt, collateral) = (usdc(10_000), weth(10 * ETH));
+        let ctx = ctx(9_600, 10_000);
+
+        // 5,000 left on the debt side
+        assert_eq!(leaves_dust(usdc(5_000).balance, U256::from(2_625 * ETH / 1_000), &debt, &collateral, &ctx), Some(false));
+        // 500 left is below MIN_LEFTOVER_BASE
+        assert_eq!(leaves_dust(usdc(9_500).balance, U256::from(4_9875 * ETH / 10_000), &debt, &collateral, &ctx), Some(true));
+        // Repaying everything leaves nothing behind
+        assert_eq!(leaves_dust(debt.balance, U256::from(525 * ETH / 100), &debt, &collateral, &ctx), Some(false));
+    }
+
+    #[test]
+    fn pair_is_sized_by_the_close_factor() {
+        let pair = evaluate_pair(&usdc(2_500), &weth(2 * ETH), &ctx(9_600, 2_500)).unwrap();
+
+        assert_eq!(pair.debt_to_cover, usdc(1_250).balance);
+        // 0.65625 WETH seized, 10% of the 0.03125 WETH bonus goes to the protocol
+        assert_eq!(pair.collateral_received, U256::from(653_125_000_000_000_000u128));
+        // 1,306.25 received - 1,250 repaid - 0.3% slippage on the received value
+        assert_eq!(pair.expected_profit_base, U256::from(5_233_125_000u64));
+    }
+
+    #[test]
+    fn pair_shrinks_instead_of_leaving_dust() {
+        // The close factor allows 1,500 of the 2,100 reserve debt, which would
+        // leave 600. Shrunk so exactly MIN_LEFTOVER_BASE stays.
+        let pair = evaluate_pair(&usdc(2_100), &weth(3 * ETH), &ctx(9_600, 3_000)).unwrap();
+
+        assert_eq!(pair.debt_to_cover, usdc(1_100).balance);
+        assert_eq!(pair.collateral_received, U256::from(574_750_000_000_000_000u128));
+        assert_eq!(pair.expected_profit_base, U256::from(4_605_150_000u64));
+    }
+
+    #[test]
+    fn full_liquidation_covers_accrued_interest() {
+        // Below the base threshold the whole debt goes, plus a 0.1% buffer
+        let pair = evaluate_pair(&usdc(1_800), &weth(2 * ETH), &ctx(9_600, 1_800)).unwrap();
+
+        assert_eq!(pair.debt_to_cover, U256::from(1_801_800_000u64));
+        assert_eq!(pair.collateral_received, U256::from(940_500_000_000_000_000u128));
+        assert_eq!(pair.expected_profit_base, U256::from(7_355_700_000u64));
+    }
+
+    #[test]
+    fn ineligible_pairs_report_why() {
+        let mut collateral = weth(2 * ETH);
+        collateral.terms.liquidation_bonus_bps = 0;
+        assert_eq!(
+            evaluate_pair(&usdc(1_800), &collateral, &ctx(9_600, 1_800)).unwrap_err(),
+            AaveSkipReason::Unprofitable
+        );
+
+        let mut debt = usdc(1_800);
+        debt.flags.paused = true;
+        assert_eq!(
+            evaluate_pair(&debt, &weth(2 * ETH), &ctx(9_600, 1_800)).unwrap_err(),
+            AaveSkipReason::ReservePaused
+        );
+
+        let mut collateral = weth(2 * ETH);
+        collateral.terms.liquidation_threshold_bps = 0;
+        assert_eq!(
+            evaluate_pair(&usdc(1_800), &collateral, &ctx(9_600, 1_800)).unwrap_err(),
+            AaveSkipReason::CollateralNotSeizable
+        );
+    }
+
+    /// Reserve configuration word with the fields `terms_from_config` reads
+    fn reserve_config(threshold: u16, bonus: u16, protocol_fee: u16, legacy_emode: u8) -> U256 {
+        (U256::from(threshold) << 16)
+            | (U256::from(bonus) << 32)
+            | (U256::one() << 56)
+            | (U256::from(protocol_fee) << 152)
+            | (U256::from(legacy_emode) << 168)
+    }
+
+    fn category(id: u8, collateral_bitmap: Option<u128>) -> EModeCategory {
+        EModeCategory {
+            id,
+            liquidation_threshold_bps: 9_500,
+            liquidation_bonus_bps: 10_100,
+            collateral_bitmap,
+            price_source: Address::zero(),
+        }
+    }
+
+    #[test]
+    fn reserve_terms_apply_outside_emode() {
+        let terms = terms_from_config(reserve_config(8_250, 10_500, 1_000, 0), None);
+
+        assert_eq!(terms.liquidation_threshold_bps, 8_250);
+        assert_eq!(terms.liquidation_bonus_bps, 500);
+        assert_eq!(terms.liquidation_protocol_fee_bps, 1_000);
+        assert!(!terms.in_emode);
+        assert!(terms.flags.active && !terms.flags.paused);
+    }
+
+    #[test]
+    fn emode_overrides_threshold_and_bonus_only() {
+        let emode = category(1, Some(1));
+        let terms = terms_from_config(reserve_config(8_250, 10_500, 1_000, 0), Some(&emode));
+
+        assert_eq!(terms.liquidation_threshold_bps, 9_500);
+        assert_eq!(terms.liquidation_bonus_bps, 100);
+        // The protocol fee stays the reserve's
+        assert_eq!(terms.liquidation_protocol_fee_bps, 1_000);
+        assert!(terms.in_emode);
+    }
+
+    #[test]
+    fn emode_membership_from_bitmap() {
+        let emode = category(1, Some(0b101 | 1 << 127));
+        let config = reserve_config(8_250, 10_500, 1_000, 0);
+
+        assert!(emode.applies_to(0, config));
+        assert!(!emode.applies_to(1, config));
+        assert!(emode.applies_to(2, config));
+        assert!(emode.applies_to(127, config));
+
+        // The bitmap wins over the legacy category bits
+        assert!(!emode.applies_to(1, reserve_config(8_250, 10_500, 1_000, 1)));
+    }
+
+    #[test]
+    fn emode_membership_from_legacy_bits() {
+        let config = reserve_config(8_250, 10_500, 1_000, 3);
+
+        assert!(category(3, None).applies_to(7, config));
+        assert!(!category(1, None).applies_to(7, config));
+        assert!(!category(3, None).applies_to(7, reserve_config(8_250, 10_500, 1_000, 0)));
+    }
+
+    #[test]
+    fn isolation_and_silo_flags_from_config() {
+        let config = reserve_config(8_250, 10_500, 1_000, 0)
+            | (U256::one() << 61)
+            | (U256::one() << 62)
+            | (U256::from(500_000_000u64) << 212);
+        let flags = ReserveFlags::from_config(config);
+
+        assert!(flags.active && flags.borrowable_in_isolation && flags.siloed_borrowing);
+        assert_eq!(flags.debt_ceiling, U256::from(500_000_000u64));
+        assert!(flags.is_isolated());
+
+        // Bits past the 40-bit ceiling are not part of it
+        let flags = ReserveFlags::from_config(U256::one() << 252);
+        assert!(!flags.is_isolated() && !flags.siloed_borrowing && !flags.borrowable_in_isolation);
+    }
+
+    #[test]
+    fn isolated_collateral_is_not_taken_as_atokens() {
+        let mut collateral = weth(2 * ETH);
+        assert_eq!(a_token_allowed(&collateral), Ok(()));
+
+        collateral.terms.flags.debt_ceiling = U256::from(100_000_000u64);
+        assert_eq!(a_token_allowed(&collateral), Err(AaveSkipReason::IsolatedATokens));
+    }
+
+    #[test]
+    fn siloed_debt_is_only_routed_into_profit_assets() {
+        let mut debt = usdc(1_800);
+        assert_eq!(route_allowed(&debt, &[]), Ok(()));
+
+        debt.flags.siloed_borrowing = true;
+        assert_eq!(route_allowed(&debt, &[]), Err(AaveSkipReason::SiloedDebt));
+        assert_eq!(route_allowed(&debt, &[debt.asset]), Ok(()));
+    }
+}
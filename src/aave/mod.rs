@@ -5,6 +5,10 @@ pub mod aave_config;
 pub mod watchlist_updater;
 pub mod aave_watchlist;
 pub mod abi_bindings;
+pub mod reserve_registry;
+pub mod position_book;
+pub mod atoken_inventory;
+pub mod plugin;
 
 use std::sync::Arc;
 
@@ -12,37 +16,102 @@ use std::sync::Arc;
  use aave_watchlist::AaveWatchList;
  use watchlist_updater::AaveWatchListUpdater;
  use aave_liquidator::AaveLiquidator;
+ use position_book::PositionBook;
+ use atoken_inventory::ATokenInventory;
 
-use crate::{aave::abi_bindings::IAaveV3Pool, common::{
-     AdminCmd, Config, Liquidator, task_manager::spawn_named_and_register}};
+use crate::{aave::abi_bindings::IAaveV3Pool, chain_profile::ChainProfile, common::{
+     AdminCmd, Config, Liquidator, task_manager::spawn_named_and_register}, constants, position_classifier::PositionClassifier};
 use tokio::sync::{mpsc, watch};
 use ethers::providers::Middleware;
+use sled::Db;
 
 
+/// Per-pool state of one Aave engine instance
+pub struct AaveInstance {
+    pub config: Arc<AaveConfig>,
+    pub watch_list: Arc<AaveWatchList>,
+    pub inventory: Arc<ATokenInventory>,
+}
+
+/// Load the chain's default pool and its extra pools. An extra pool that
+/// fails to load is skipped, the default pool must load.
+pub async fn load_instances<M: Middleware + 'static>(
+    chain: Arc<ChainProfile>,
+    client: Arc<M>,
+    db: Arc<Db>,
+) -> anyhow::Result<Vec<AaveInstance>> {
+    let mut configs = vec![AaveConfig::load(chain.clone())?];
+    let extra_pools = chain.aave.iter().flat_map(|aave| aave.extra_pools.iter());
+
+    for spec in extra_pools {
+        match AaveConfig::for_pool(chain.clone(), spec, client.clone()).await {
+            Ok(config) => configs.push(config),
+            Err(e) => tracing::error!("❌ Failed to resolve Aave pool {}: {:?}", spec.name, e),
+        }
+    }
+
+    let mut instances = Vec::with_capacity(configs.len());
+    for config in configs {
+        let name = config.name.clone();
+
+        let config = match load_config(config, client.clone()).await {
+            Ok(config) => config,
+            Err(e) if name == constants::AAVE_DEFAULT_POOL => return Err(e),
+            Err(e) => {
+                tracing::error!("❌ Skipping Aave pool {}: {:?}", name, e);
+                continue;
+            }
+        };
+
+        let namespace = config.namespace();
+        instances.push(AaveInstance {
+            watch_list: Arc::new(AaveWatchList::new(db.clone(), &namespace)?),
+            inventory: Arc::new(ATokenInventory::new(db.clone(), &namespace)?),
+            config,
+        });
+    }
+
+    Ok(instances)
+}
+
+/// Resolve the pool's liquidation rules and reserve set
+async fn load_config<M: Middleware + 'static>(
+    mut aave_config: AaveConfig,
+    client: Arc<M>,
+) -> anyhow::Result<Arc<AaveConfig>> {
+    aave_config.load_liquidation_rules(client.clone()).await;
+
+    match aave_config.populate_reserves(client).await {
+        Ok(_) => tracing::info!("✅ Aave reserves populated successfully for pool {}", aave_config.name),
+        Err(e) => {
+            tracing::error!("❌ Failed to populate Aave reserves: {:?}", e);
+            return Err(anyhow::anyhow!("Failed to populate Aave reserves"));
+        }
+    }
+
+    Ok(Arc::new(aave_config))
+}
+
 pub async fn start_engine<M: Middleware  + 'static>(
     client: Arc<M>,
     shutdown_rx: watch::Receiver<bool>,
     prune_rx: mpsc::Receiver<AdminCmd>,
-    watch_list: Arc<AaveWatchList>,
-    pool: Arc<IAaveV3Pool<M>>
-    
+    instance: AaveInstance,
+    classifier: Arc<PositionClassifier>,
 ) -> anyhow::Result<Arc<dyn Liquidator>>{
-    let mut aave_config = AaveConfig::load()?;
-   
-    match aave_config.populate_vdebt_tokens(client.clone()).await {
-        Ok(_) => tracing::info!("✅ Aave  vdebt tokens populated successfully"),
-        Err(e) => {
-            tracing::error!("❌ Failed to populate Aave vdebt tokens: {:?}", e);
-            return Err(anyhow::anyhow!("Failed to populate Aave vdebt tokens"));
-        }
-    }
+    let AaveInstance { config: aave_config, watch_list, inventory } = instance;
 
-    let aave_config = Arc::new(aave_config);
+    let pool = Arc::new(IAaveV3Pool::new(aave_config.lending_pool, client.clone()));
+    let book = Arc::new(PositionBook::new());
+    book.load_reserves(&pool, &aave_config).await?;
 
     let aave_liq = AaveLiquidator::new(
         aave_config.clone(),
         client.clone(),
-        watch_list.clone()
+        watch_list.clone(),
+        book.clone(),
+        inventory,
+        classifier,
     );
     
     spawn_named_and_register("aave_watchlist_updater", async move {
@@ -51,6 +120,7 @@ pub async fn start_engine<M: Middleware  + 'static>(
             watch_list.clone(),
             pool.clone(),
             aave_config.clone(),
+            book.clone(),
             shutdown_rx.clone(), 
             prune_rx
         );
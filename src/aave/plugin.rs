@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use ethers::providers::Middleware;
+use sled::Db;
+use tokio::sync::mpsc;
+
+use crate::{
+    bootstrap_engine::{aave_bootstrap::AaveBootstrap, bootstrap_state::BootstrapState, Bootstrap},
+    chain_profile::ChainProfile,
+    common::plugin::{Engine, EngineContext, ProtocolPlugin},
+};
+
+use super::{abi_bindings::IAaveV3Pool, AaveInstance};
+
+/// Aave v3: one engine per pool
+pub struct AavePlugin<M> {
+    client: Arc<M>,
+    instances: Vec<AaveInstance>,
+}
+
+impl<M: Middleware + 'static> AavePlugin<M> {
+    pub async fn load(chain: Arc<ChainProfile>, client: Arc<M>, db: Arc<Db>) -> anyhow::Result<Self> {
+        let instances = super::load_instances(chain, client.clone(), db).await?;
+        Ok(Self { client, instances })
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> ProtocolPlugin for AavePlugin<M> {
+    fn name(&self) -> &'static str {
+        "aave"
+    }
+
+    fn bootstraps(&self, state: Arc<BootstrapState>) -> Vec<Arc<dyn Bootstrap>> {
+        self.instances
+            .iter()
+            .map(|instance| -> Arc<dyn Bootstrap> {
+                Arc::new(AaveBootstrap::new(
+                    IAaveV3Pool::new(instance.config.lending_pool, self.client.clone()),
+                    instance.watch_list.clone(),
+                    state.clone(),
+                    self.client.clone(),
+                    instance.config.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    async fn start(self: Box<Self>, ctx: EngineContext) -> anyhow::Result<Vec<Engine>> {
+        let mut engines = Vec::with_capacity(self.instances.len());
+
+        for instance in self.instances {
+            let name = instance.config.namespace();
+            let (admin_tx, admin_rx) = mpsc::channel(64);
+            let liquidator = super::start_engine(
+                self.client.clone(),
+                ctx.shutdown_rx.clone(),
+                admin_rx,
+                instance,
+                ctx.classifier.clone(),
+            ).await?;
+
+            engines.push(Engine { name, liquidator, admin_tx });
+        }
+
+        Ok(engines)
+    }
+}
@@ -0,0 +1,587 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use dashmap::{DashMap, DashSet};
+use ethers::{
+    providers::Middleware,
+    types::{Address, U256},
+};
+use futures_util::{stream, StreamExt};
+
+use super::{
+    abi_bindings::{AaveOracle, IAaveV3Pool, IAaveV3PoolEvents, ReserveDataUpdatedFilter, UiPoolDataProvider},
+    aave_config::AaveConfig,
+    helpers,
+};
+
+use crate::{
+    common::get_token_decimals,
+    constants::{HF_CONFIRM_THRESHOLD_BPS, WAD},
+};
+
+const BPS: u128 = 10_000;
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+fn ray() -> U256 {
+    U256::exp10(27)
+}
+
+fn ray_mul(a: U256, b: U256) -> U256 {
+    (a * b + ray() / 2) / ray()
+}
+
+fn ray_div(a: U256, b: U256) -> U256 {
+    (a * ray() + b / 2) / b
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Reserve state needed to turn scaled balances into live ones
+#[derive(Debug, Clone, Copy)]
+struct ReserveState {
+    configuration: U256,
+    liquidity_index: U256,
+    liquidity_rate: U256,
+    variable_borrow_index: U256,
+    variable_borrow_rate: U256,
+    last_update: u64,
+    decimals: u8,
+}
+
+impl ReserveState {
+    /// Liquidity index accrued to `at` (linear interest)
+    fn normalized_income(&self, at: u64) -> U256 {
+        let elapsed = U256::from(at.saturating_sub(self.last_update));
+        let factor = ray() + self.liquidity_rate * elapsed / U256::from(SECONDS_PER_YEAR);
+
+        ray_mul(factor, self.liquidity_index)
+    }
+
+    /// Variable borrow index accrued to `at`, using the pool's
+    /// three-term binomial approximation of compounded interest
+    fn normalized_debt(&self, at: u64) -> U256 {
+        let exp = at.saturating_sub(self.last_update);
+        if exp == 0 {
+            return self.variable_borrow_index;
+        }
+
+        let year = U256::from(SECONDS_PER_YEAR);
+        let rate = self.variable_borrow_rate;
+
+        let exp_minus_one = U256::from(exp - 1);
+        let exp_minus_two = U256::from(exp.saturating_sub(2));
+        let exp = U256::from(exp);
+
+        let base_power_two = ray_mul(rate, rate) / (year * year);
+        let base_power_three = ray_mul(base_power_two, rate) / year;
+
+        let second_term = exp * exp_minus_one * base_power_two / 2;
+        let third_term = exp * exp_minus_one * exp_minus_two * base_power_three / 6;
+
+        let factor = ray() + rate * exp / year + second_term + third_term;
+
+        ray_mul(factor, self.variable_borrow_index)
+    }
+
+    fn liquidation_threshold_bps(&self) -> u16 {
+        ((self.configuration >> 16) & U256::from(0xFFFF)).as_u32() as u16
+    }
+}
+
+/// Scaled balances of one borrower, keyed by reserve
+#[derive(Debug, Clone, Default)]
+struct BorrowerPosition {
+    /// Scaled aToken balances of reserves enabled as collateral
+    collateral: HashMap<Address, U256>,
+    /// Scaled variable debt balances
+    debt: HashMap<Address, U256>,
+    emode_id: u8,
+}
+
+/// In-memory model of watched Aave borrowers.
+///
+/// Scaled balances are seeded from `getUserReservesData` and kept current
+/// from pool events, so health factors can be computed locally from the
+/// cached reserve indexes and one batched oracle call per cycle. Events the
+/// model cannot apply exactly mark the borrower stale, and stale borrowers
+/// are re-read from the chain on the next sync.
+#[derive(Debug, Default)]
+pub struct PositionBook {
+    positions: DashMap<Address, BorrowerPosition>,
+    reserves: DashMap<Address, ReserveState>,
+    prices: DashMap<Address, U256>,
+    stale: DashSet<Address>,
+}
+
+impl PositionBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //
+    // ─────────────────────────────────────────────────────────────
+    // Event Updates
+    // ─────────────────────────────────────────────────────────────
+    //
+
+    pub fn apply_event(&self, event: &IAaveV3PoolEvents) {
+        match event {
+            // Needs the block timestamp, applied through `on_reserve_updated`
+            IAaveV3PoolEvents::ReserveDataUpdatedFilter(_) => {}
+
+            IAaveV3PoolEvents::SupplyFilter(f) => {
+                // Whether a first supply is enabled as collateral depends on
+                // the reserve and the borrower's isolation state
+                let scaled = self.scale_income(f.reserve, f.amount);
+                self.update(f.on_behalf_of, |p| match (p.collateral.get_mut(&f.reserve), scaled) {
+                    (Some(balance), Some(scaled)) => {
+                        *balance += scaled;
+                        true
+                    }
+                    _ => false,
+                });
+            }
+
+            IAaveV3PoolEvents::WithdrawFilter(f) => {
+                let scaled = self.scale_income(f.reserve, f.amount);
+                self.update(f.user, |p| match (p.collateral.get_mut(&f.reserve), scaled) {
+                    (Some(balance), Some(scaled)) => {
+                        *balance = balance.saturating_sub(scaled);
+                        true
+                    }
+                    // Not used as collateral, the health factor is unaffected
+                    (None, _) => true,
+                    _ => false,
+                });
+            }
+
+            IAaveV3PoolEvents::BorrowFilter(f) => {
+                let scaled = self.scale_debt(f.reserve, f.amount);
+                self.update(f.on_behalf_of, |p| match scaled {
+                    Some(scaled) => {
+                        *p.debt.entry(f.reserve).or_default() += scaled;
+                        true
+                    }
+                    None => false,
+                });
+            }
+
+            IAaveV3PoolEvents::RepayFilter(f) => {
+                // Repaying with aTokens also burns collateral
+                if f.use_a_tokens {
+                    self.mark_stale(f.user);
+                    return;
+                }
+
+                let scaled = self.scale_debt(f.reserve, f.amount);
+                self.update(f.user, |p| match (p.debt.get_mut(&f.reserve), scaled) {
+                    (Some(balance), Some(scaled)) => {
+                        *balance = balance.saturating_sub(scaled);
+                        true
+                    }
+                    _ => false,
+                });
+            }
+
+            IAaveV3PoolEvents::LiquidationCallFilter(f) => self.mark_stale(f.user),
+            IAaveV3PoolEvents::UserEModeSetFilter(f) => self.mark_stale(f.user),
+            IAaveV3PoolEvents::ReserveUsedAsCollateralEnabledFilter(f) => self.mark_stale(f.user),
+            IAaveV3PoolEvents::ReserveUsedAsCollateralDisabledFilter(f) => self.mark_stale(f.user),
+
+            _ => {}
+        }
+    }
+
+    /// aToken transfers move scaled collateral between two accounts
+    pub fn on_atoken_transfer(&self, from: Address, to: Address) {
+        self.mark_stale(from);
+        self.mark_stale(to);
+    }
+
+    pub fn mark_stale(&self, borrower: Address) {
+        if self.positions.contains_key(&borrower) {
+            self.stale.insert(borrower);
+        }
+    }
+
+    /// New indexes and rates, accrued up to `block_timestamp` of the block
+    /// that emitted the event
+    pub fn on_reserve_updated(&self, f: &ReserveDataUpdatedFilter, block_timestamp: u64) {
+        if let Some(mut state) = self.reserves.get_mut(&f.reserve) {
+            state.liquidity_index = f.liquidity_index;
+            state.liquidity_rate = f.liquidity_rate;
+            state.variable_borrow_index = f.variable_borrow_index;
+            state.variable_borrow_rate = f.variable_borrow_rate;
+            state.last_update = block_timestamp;
+        }
+    }
+
+    /// Apply `change` to a tracked borrower, marking it stale when the
+    /// change cannot be modelled
+    fn update(&self, borrower: Address, change: impl FnOnce(&mut BorrowerPosition) -> bool) {
+        let applied = match self.positions.get_mut(&borrower) {
+            Some(mut position) => change(&mut position),
+            None => return,
+        };
+
+        if !applied {
+            self.stale.insert(borrower);
+        }
+    }
+
+    fn scale_income(&self, reserve: Address, amount: U256) -> Option<U256> {
+        let state = self.reserves.get(&reserve)?;
+        Some(ray_div(amount, state.liquidity_index))
+    }
+
+    fn scale_debt(&self, reserve: Address, amount: U256) -> Option<U256> {
+        let state = self.reserves.get(&reserve)?;
+        Some(ray_div(amount, state.variable_borrow_index))
+    }
+
+    //
+    // ─────────────────────────────────────────────────────────────
+    // Chain Sync
+    // ─────────────────────────────────────────────────────────────
+    //
+
+    /// (Re)load the state of every enabled reserve
+    pub async fn load_reserves<M: Middleware + 'static>(
+        &self,
+        pool: &IAaveV3Pool<M>,
+        config: &AaveConfig,
+    ) -> Result<()> {
+        stream::iter(config.reserves.enabled())
+            .for_each_concurrent(8, |asset| async move {
+                if let Err(e) = self.load_reserve(pool, asset).await {
+                    tracing::warn!("Failed to load Aave reserve state {:?}: {:?}", asset, e);
+                }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    async fn load_reserve<M: Middleware + 'static>(
+        &self,
+        pool: &IAaveV3Pool<M>,
+        asset: Address,
+    ) -> Result<()> {
+        let data = pool.get_reserve_data(asset).call().await?;
+        let decimals = get_token_decimals(asset, pool.client()).await?;
+
+        self.reserves.insert(asset, ReserveState {
+            configuration: data.configuration.data,
+            liquidity_index: U256::from(data.liquidity_index),
+            liquidity_rate: U256::from(data.current_liquidity_rate),
+            variable_borrow_index: U256::from(data.variable_borrow_index),
+            variable_borrow_rate: U256::from(data.current_variable_borrow_rate),
+            last_update: data.last_update_timestamp,
+            decimals,
+        });
+
+        Ok(())
+    }
+
+    /// Bring the book in line with the watchlist: drop unwatched borrowers,
+    /// read new and stale ones from the chain and refresh oracle prices
+    pub async fn sync<M: Middleware + 'static>(
+        &self,
+        borrowers: &HashSet<Address>,
+        pool: &IAaveV3Pool<M>,
+        ui_provider: &UiPoolDataProvider<M>,
+        oracle: &AaveOracle<M>,
+        config: &AaveConfig,
+    ) -> Result<()> {
+        self.positions.retain(|b, _| borrowers.contains(b));
+        self.stale.retain(|b| borrowers.contains(b));
+
+        let to_load: Vec<Address> = borrowers
+            .iter()
+            .filter(|b| !self.positions.contains_key(*b) || self.stale.contains(*b))
+            .copied()
+            .collect();
+
+        if !to_load.is_empty() {
+            tracing::debug!("Position book: loading {} borrowers", to_load.len());
+        }
+
+        stream::iter(to_load)
+            .for_each_concurrent(10, |borrower| async move {
+                if let Err(e) = self.load_borrower(borrower, pool, ui_provider, config).await {
+                    tracing::warn!("Failed to load Aave position for {:?}: {:?}", borrower, e);
+                }
+            })
+            .await;
+
+        self.refresh_prices(oracle, config).await
+    }
+
+    async fn load_borrower<M: Middleware + 'static>(
+        &self,
+        borrower: Address,
+        pool: &IAaveV3Pool<M>,
+        ui_provider: &UiPoolDataProvider<M>,
+        config: &AaveConfig,
+    ) -> Result<()> {
+        let (reserves, emode_id) = ui_provider
+            .get_user_reserves_data(config.pool_address_provider, borrower)
+            .call()
+            .await?;
+
+        // Caches the category so health factors can be computed offline
+        helpers::emode_category(emode_id, pool, config).await?;
+
+        let mut position = BorrowerPosition {
+            emode_id,
+            ..Default::default()
+        };
+
+        for reserve in reserves {
+            let asset = reserve.underlying_asset;
+
+            if !self.reserves.contains_key(&asset) {
+                self.load_reserve(pool, asset).await?;
+            }
+
+            if reserve.usage_as_collateral_enabled_on_user && !reserve.scaled_a_token_balance.is_zero() {
+                position.collateral.insert(asset, reserve.scaled_a_token_balance);
+            }
+
+            if !reserve.scaled_variable_debt.is_zero() {
+                position.debt.insert(asset, reserve.scaled_variable_debt);
+            }
+        }
+
+        self.positions.insert(borrower, position);
+        self.stale.remove(&borrower);
+
+        Ok(())
+    }
+
+    pub async fn refresh_prices<M: Middleware + 'static>(
+        &self,
+        oracle: &AaveOracle<M>,
+        config: &AaveConfig,
+    ) -> Result<()> {
+        let emode_ids: HashSet<u8> = self.positions.iter().map(|p| p.emode_id).collect();
+
+        let mut assets: Vec<Address> = self.reserves.iter().map(|r| *r.key()).collect();
+        assets.extend(
+            emode_ids
+                .into_iter()
+                .filter_map(|id| config.reserves.emode_category(id))
+                .map(|c| c.price_source)
+                .filter(|src| !src.is_zero()),
+        );
+        assets.sort();
+        assets.dedup();
+
+        if assets.is_empty() {
+            return Ok(());
+        }
+
+        let prices = oracle.get_assets_prices(assets.clone()).call().await?;
+
+        for (asset, price) in assets.into_iter().zip(prices) {
+            self.prices.insert(asset, price);
+        }
+
+        Ok(())
+    }
+
+    //
+    // ─────────────────────────────────────────────────────────────
+    // Health Factor
+    // ─────────────────────────────────────────────────────────────
+    //
+
+    /// Locally computed health factor (WAD), `None` if the borrower is
+    /// unknown, stale or depends on state we do not have
+    pub fn health_factor(&self, borrower: Address, config: &AaveConfig) -> Option<U256> {
+        if self.stale.contains(&borrower) {
+            return None;
+        }
+
+        let position = self.positions.get(&borrower)?;
+        let at = now();
+
+        let emode = match position.emode_id {
+            0 => None,
+            id => Some(config.reserves.emode_category(id)?),
+        };
+        let price_source = emode
+            .map(|c| c.price_source)
+            .filter(|src| !src.is_zero());
+
+        let mut weighted_collateral = U256::zero();
+        for (asset, scaled) in position.collateral.iter() {
+            let state = *self.reserves.get(asset)?;
+
+            let (threshold, source) = match emode {
+                Some(category) if category.applies_to(config.reserves.id(asset)?, state.configuration) => {
+                    (category.liquidation_threshold_bps, price_source.unwrap_or(*asset))
+                }
+                _ => (state.liquidation_threshold_bps(), *asset),
+            };
+
+            let balance = ray_mul(*scaled, state.normalized_income(at));
+            let price = *self.prices.get(&source)?;
+            let value = balance * price / U256::exp10(state.decimals as usize);
+
+            weighted_collateral += value * U256::from(threshold) / U256::from(BPS);
+        }
+
+        let mut total_debt = U256::zero();
+        for (asset, scaled) in position.debt.iter() {
+            let state = *self.reserves.get(asset)?;
+
+            let balance = ray_mul(*scaled, state.normalized_debt(at));
+            let price = *self.prices.get(&price_source.unwrap_or(*asset))?;
+
+            total_debt += balance * price / U256::exp10(state.decimals as usize);
+        }
+
+        if total_debt.is_zero() {
+            return Some(U256::MAX);
+        }
+
+        Some(weighted_collateral * *WAD / total_debt)
+    }
+
+    /// Borrowers with collateral or debt priced by any of `assets`
+    pub fn exposed_to(&self, assets: &HashSet<Address>, config: &AaveConfig) -> HashSet<Address> {
+        self.positions
+            .iter()
+            .filter(|p| {
+                let price_source = config
+                    .reserves
+                    .emode_category(p.emode_id)
+                    .map(|c| c.price_source);
+
+                p.collateral
+                    .keys()
+                    .chain(p.debt.keys())
+                    .chain(price_source.iter())
+                    .any(|a| assets.contains(a))
+            })
+            .map(|p| *p.key())
+            .collect()
+    }
+
+    /// Borrowers worth an on-chain check: near or below 1.0 locally, or
+    /// not modelled
+    pub fn at_risk(&self, borrowers: &HashSet<Address>, config: &AaveConfig) -> Vec<Address> {
+        let threshold = U256::from(HF_CONFIRM_THRESHOLD_BPS) * *WAD / U256::from(BPS);
+
+        borrowers
+            .iter()
+            .filter(|b| {
+                self.health_factor(**b, config)
+                    .is_none_or(|hf| hf < threshold)
+            })
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR: u64 = SECONDS_PER_YEAR;
+    const T0: u64 = 1_700_000_000;
+
+    fn ray_pct(pct: u64) -> U256 {
+        ray() * U256::from(pct) / U256::from(100)
+    }
+
+    fn reserve(index: U256, rate: U256) -> ReserveState {
+        ReserveState {
+            configuration: U256::zero(),
+            liquidity_index: index,
+            liquidity_rate: rate,
+            variable_borrow_index: index,
+            variable_borrow_rate: rate,
+            last_update: T0,
+            decimals: 18,
+        }
+    }
+
+    #[test]
+    fn ray_math_rounds_half_up() {
+        assert_eq!(ray_mul(ray_pct(150), ray_pct(200)), ray_pct(300));
+        assert_eq!(ray_mul(U256::one(), ray() / 2), U256::one());
+        assert_eq!(ray_mul(U256::one(), ray() / 2 - 1), U256::zero());
+
+        assert_eq!(ray_div(ray_pct(300), ray_pct(200)), ray_pct(150));
+        assert_eq!(ray_div(U256::one(), ray_pct(200)), U256::one());
+        assert_eq!(ray_div(U256::one(), ray_pct(300)), U256::zero());
+    }
+
+    #[test]
+    fn income_accrues_linearly() {
+        let state = reserve(ray_pct(110), ray_pct(5));
+
+        assert_eq!(state.normalized_income(T0), ray_pct(110));
+        // 1.10 * (1 + 5%)
+        assert_eq!(state.normalized_income(T0 + YEAR), U256::from_dec_str("1155000000000000000000000000").unwrap());
+        assert_eq!(state.normalized_income(T0 + YEAR / 2), U256::from_dec_str("1127500000000000000000000000").unwrap());
+        // A timestamp before the last update never moves the index back
+        assert_eq!(state.normalized_income(T0 - 60), ray_pct(110));
+    }
+
+    #[test]
+    fn debt_accrues_with_the_binomial_approximation() {
+        let state = reserve(ray(), ray_pct(10));
+
+        assert_eq!(state.normalized_debt(T0), ray());
+        assert_eq!(state.normalized_debt(T0 - 60), ray());
+        assert_eq!(state.normalized_debt(T0 + 1), U256::from_dec_str("1000000003170979198376458650").unwrap());
+        // 10% for a year: just under e^0.1, above the linear 1.10
+        assert_eq!(state.normalized_debt(T0 + YEAR), U256::from_dec_str("1105162042821782412575504000").unwrap());
+
+        let state = reserve(ray_pct(120), ray_pct(10));
+        assert_eq!(state.normalized_debt(T0 + YEAR), U256::from_dec_str("1326194451386138895090604800").unwrap());
+    }
+
+    #[test]
+    fn reserve_update_accrues_from_the_event_block() {
+        let asset = Address::from_low_u64_be(1);
+        let book = PositionBook::new();
+        book.reserves.insert(asset, reserve(ray(), ray_pct(10)));
+
+        book.on_reserve_updated(
+            &ReserveDataUpdatedFilter {
+                reserve: asset,
+                liquidity_rate: ray_pct(5),
+                stable_borrow_rate: U256::zero(),
+                variable_borrow_rate: ray_pct(8),
+                liquidity_index: ray_pct(110),
+                variable_borrow_index: ray_pct(120),
+            },
+            T0 + YEAR,
+        );
+
+        let state = *book.reserves.get(&asset).unwrap();
+        assert_eq!(state.last_update, T0 + YEAR);
+        assert_eq!(state.normalized_income(T0 + YEAR), ray_pct(110));
+        assert_eq!(state.normalized_debt(T0 + YEAR), ray_pct(120));
+        assert_eq!(state.normalized_income(T0 + 2 * YEAR), U256::from_dec_str("1155000000000000000000000000").unwrap());
+
+        // Unknown reserves are ignored
+        book.on_reserve_updated(
+            &ReserveDataUpdatedFilter { reserve: Address::from_low_u64_be(2), ..Default::default() },
+            T0,
+        );
+        assert!(book.reserves.get(&Address::from_low_u64_be(2)).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{collections::HashSet, sync::RwLock};
+use anyhow::Result;
+use dashmap::DashMap;
+use ethers::{providers::Middleware, types::Address};
+use futures_util::{StreamExt, TryStreamExt, stream};
+
+use super::{abi_bindings::IAaveV3Pool, types::EModeCategory};
+
+/// Token addresses backing a single Aave reserve
+#[derive(Debug, Clone, Copy)]
+pub struct ReserveTokens {
+    pub id: u16,
+    pub a_token: Address,
+    pub vdebt_token: Address,
+}
+
+/// Live view of the pool's reserves.
+///
+/// Seeded from `getReservesList` at startup and extended at runtime
+/// whenever governance lists a new reserve (`ReserveInitialized`).
+/// The allow/deny lists only decide which reserves we act on; token
+/// addresses are kept for every listed reserve. The lists can be
+/// swapped on a config reload.
+#[derive(Debug, Default)]
+pub struct ReserveRegistry {
+    tokens: DashMap<Address, ReserveTokens>,
+    emode_categories: DashMap<u8, EModeCategory>,
+    allowlist: RwLock<HashSet<Address>>,
+    denylist: RwLock<HashSet<Address>>,
+}
+
+impl ReserveRegistry {
+    pub fn new(allowlist: HashSet<Address>, denylist: HashSet<Address>) -> Self {
+        Self {
+            tokens: DashMap::new(),
+            emode_categories: DashMap::new(),
+            allowlist: RwLock::new(allowlist),
+            denylist: RwLock::new(denylist),
+        }
+    }
+
+    pub fn set_filters(&self, allowlist: HashSet<Address>, denylist: HashSet<Address>) {
+        *self.allowlist.write().unwrap_or_else(|e| e.into_inner()) = allowlist;
+        *self.denylist.write().unwrap_or_else(|e| e.into_inner()) = denylist;
+    }
+
+    /// Load every reserve listed on the pool
+    pub async fn load<M: Middleware + 'static>(&self, pool: &IAaveV3Pool<M>) -> Result<()> {
+        let assets = pool.get_reserves_list().call().await?;
+
+        let _: Vec<ReserveTokens> = stream::iter(assets)
+            .map(|asset| self.fetch(pool, asset))
+            .buffer_unordered(8)
+            .try_collect()
+            .await?;
+
+        tracing::info!(
+            "Aave reserve registry loaded: {} listed, {} enabled",
+            self.tokens.len(),
+            self.enabled().len()
+        );
+
+        Ok(())
+    }
+
+    /// Resolve a reserve's tokens from the pool and register them
+    pub async fn fetch<M: Middleware + 'static>(
+        &self,
+        pool: &IAaveV3Pool<M>,
+        asset: Address,
+    ) -> Result<ReserveTokens> {
+        let data = pool.get_reserve_data(asset).call().await?;
+        let tokens = ReserveTokens {
+            id: data.id,
+            a_token: data.a_token_address,
+            vdebt_token: data.variable_debt_token_address,
+        };
+
+        self.register(asset, tokens);
+        Ok(tokens)
+    }
+
+    /// Register a reserve, returns true if it was not known before
+    pub fn register(&self, asset: Address, tokens: ReserveTokens) -> bool {
+        self.tokens.insert(asset, tokens).is_none()
+    }
+
+    /// Whether the reserve is listed and passes the allow/deny lists
+    pub fn contains(&self, asset: &Address) -> bool {
+        self.tokens.contains_key(asset) && self.is_allowed(asset)
+    }
+
+    pub fn is_allowed(&self, asset: &Address) -> bool {
+        if self.denylist.read().unwrap_or_else(|e| e.into_inner()).contains(asset) {
+            return false;
+        }
+
+        let allowlist = self.allowlist.read().unwrap_or_else(|e| e.into_inner());
+        allowlist.is_empty() || allowlist.contains(asset)
+    }
+
+    pub fn id(&self, asset: &Address) -> Option<u16> {
+        self.tokens.get(asset).map(|t| t.id)
+    }
+
+    pub fn a_token(&self, asset: &Address) -> Option<Address> {
+        self.tokens.get(asset).map(|t| t.a_token)
+    }
+
+    pub fn vdebt_token(&self, asset: &Address) -> Option<Address> {
+        self.tokens.get(asset).map(|t| t.vdebt_token)
+    }
+
+    /// All reserves we act on
+    pub fn enabled(&self) -> Vec<Address> {
+        self.tokens
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|asset| self.is_allowed(asset))
+            .collect()
+    }
+
+    pub fn emode_category(&self, id: u8) -> Option<EModeCategory> {
+        self.emode_categories.get(&id).map(|c| *c)
+    }
+
+    pub fn cache_emode_category(&self, category: EModeCategory) {
+        self.emode_categories.insert(category.id, category);
+    }
+
+    /// Drop cached e-mode categories so governance updates are picked up
+    pub fn clear_emode_categories(&self) {
+        self.emode_categories.clear();
+    }
+}
@@ -1,10 +1,40 @@
-use ethers::{types::{Address, U256, Bytes}};
+use std::{collections::HashSet, fmt};
 
-pub struct CollateralCandidate{
+use ethers::{types::{Address, U256, Bytes, H256}};
+use serde::Deserialize;
+
+use crate::common::abi_bindings::LiquidationParams;
+
+/// A borrower's debt in one reserve, priced in the oracle base currency
+#[derive(Debug, Clone, Copy)]
+pub struct DebtPosition {
     pub asset: Address,
-    pub liquidation_bonus_bps: u16,
-    pub seize_amount: U256,
-    pub usd_value: U256
+    pub balance: U256,
+    pub price: U256,
+    pub decimals: u8,
+    pub flags: ReserveFlags,
+}
+
+/// A borrower's collateral in one reserve, priced in the oracle base currency
+#[derive(Debug, Clone, Copy)]
+pub struct CollateralPosition {
+    pub asset: Address,
+    pub balance: U256,
+    pub price: U256,
+    pub decimals: u8,
+    pub terms: CollateralTerms,
+}
+
+/// Expected outcome of liquidating one debt/collateral pair
+#[derive(Debug, Clone, Copy)]
+pub struct PairEstimate {
+    pub debt_asset: Address,
+    pub collateral_asset: Address,
+    pub debt_to_cover: U256,
+    /// Collateral left to the liquidator once the protocol fee is taken
+    pub collateral_received: U256,
+    /// Oracle value of the collateral received, minus repaid debt and swap slippage
+    pub expected_profit_base: U256,
 }
 
 
@@ -16,6 +46,196 @@ pub struct LiquidationCandidate {
     pub swap_target: Address,
     pub swap_proxy: Address,
     pub swap_data: Bytes,
-    pub min_amt_out: U256
-    
-}
\ No newline at end of file
+    pub min_amt_out: U256,
+    /// Take the collateral as aTokens, repaid by the keeper from its own
+    /// debt-asset balance with a direct `liquidationCall`. `min_amt_out`
+    /// is then the expected amount of aTokens.
+    pub receive_a_token: bool,
+}
+
+/// Liquidation parameters that apply to a collateral reserve for one borrower
+#[derive(Debug, Clone, Copy)]
+pub struct CollateralTerms {
+    pub liquidation_threshold_bps: u16,
+    /// Bonus on top of the repaid value, e.g. 500 for 5%
+    pub liquidation_bonus_bps: u16,
+    /// Share of the bonus kept by the protocol
+    pub liquidation_protocol_fee_bps: u16,
+    /// Whether the borrower's e-mode category overrides the reserve values
+    pub in_emode: bool,
+    pub flags: ReserveFlags,
+}
+
+/// Reserve state and borrowing restrictions from the configuration bitmap
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReserveFlags {
+    pub active: bool,
+    pub paused: bool,
+    pub borrowable_in_isolation: bool,
+    pub siloed_borrowing: bool,
+    /// Isolation-mode debt ceiling (2 decimals), zero when not isolated
+    pub debt_ceiling: U256,
+}
+
+impl ReserveFlags {
+    pub fn from_config(config: U256) -> Self {
+        let bit = |i: usize| config.bit(i);
+
+        Self {
+            active: bit(56),
+            paused: bit(60),
+            borrowable_in_isolation: bit(61),
+            siloed_borrowing: bit(62),
+            debt_ceiling: (config >> 212) & ((U256::one() << 40) - 1),
+        }
+    }
+
+    pub fn is_isolated(&self) -> bool {
+        !self.debt_ceiling.is_zero()
+    }
+}
+
+/// Why a borrower or pair produced no liquidation. Pair reasons are
+/// ordered from furthest to closest to executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AaveSkipReason {
+    /// Pool rejects liquidations on inactive reserves
+    ReserveInactive,
+    /// Pool rejects liquidations on paused reserves
+    ReservePaused,
+    /// Zero liquidation threshold, the pool does not seize it
+    CollateralNotSeizable,
+    /// Close factor and dust rules leave nothing to liquidate
+    DustLimit,
+    /// Bonus does not cover the protocol fee and swap costs
+    Unprofitable,
+    /// Isolated collateral is never enabled as collateral on receipt, so
+    /// it is not taken as aTokens
+    IsolatedATokens,
+    /// A routed liquidation leaves its surplus in the debt asset, a siloed
+    /// one is only accepted when it is a profit asset
+    SiloedDebt,
+    Healthy,
+    NoPositions,
+    /// No route, liquidity or inventory to realize any ranked pair
+    NotExecutable,
+}
+
+impl fmt::Display for AaveSkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::ReserveInactive => "reserve inactive",
+            Self::ReservePaused => "reserve paused",
+            Self::CollateralNotSeizable => "collateral not seizable",
+            Self::DustLimit => "close factor / dust limit",
+            Self::Unprofitable => "unprofitable",
+            Self::IsolatedATokens => "isolated collateral not taken as aTokens",
+            Self::SiloedDebt => "siloed debt outside the profit assets",
+            Self::Healthy => "healthy",
+            Self::NoPositions => "no eligible positions",
+            Self::NotExecutable => "no executable pair",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for AaveSkipReason {}
+
+/// Efficiency mode category a borrower has opted into
+#[derive(Debug, Clone, Copy)]
+pub struct EModeCategory {
+    pub id: u8,
+    pub liquidation_threshold_bps: u16,
+    pub liquidation_bonus_bps: u16,
+    /// Collateral membership bitmap (v3.2+), `None` on older pools
+    pub collateral_bitmap: Option<u128>,
+    /// Category price source (pre-3.2 only), zero when unset
+    pub price_source: Address,
+}
+
+impl EModeCategory {
+    /// Whether the category's parameters apply to a collateral reserve
+    pub fn applies_to(&self, reserve_id: u16, reserve_config: U256) -> bool {
+        match self.collateral_bitmap {
+            Some(bitmap) => (bitmap >> reserve_id) & 1 == 1,
+            // Pre-3.2 reserves carry their category in bits 168-175
+            None => ((reserve_config >> 168) & U256::from(0xFF)).as_u32() == self.id as u32,
+        }
+    }
+}
+
+/// Close factor and dust parameters of the pool's `LiquidationLogic` (v3.3+)
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidationRules {
+    /// Above this health factor (WAD) the close factor may apply
+    pub close_factor_hf_threshold: U256,
+    /// Debt and collateral values (base currency) from which the close factor applies
+    pub min_base_max_close_factor_threshold: U256,
+    /// Smallest debt or collateral value (base currency) a partial liquidation may leave
+    pub min_leftover_base: U256,
+    pub default_close_factor_bps: u16,
+}
+
+impl Default for LiquidationRules {
+    fn default() -> Self {
+        Self {
+            close_factor_hf_threshold: U256::from(95) * U256::exp10(16),
+            min_base_max_close_factor_threshold: U256::from(2_000) * U256::exp10(8),
+            min_leftover_base: U256::from(1_000) * U256::exp10(8),
+            default_close_factor_bps: 5_000,
+        }
+    }
+}
+
+/// Borrower-wide inputs to liquidation sizing
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidationContext {
+    pub health_factor: U256,
+    pub total_debt_base: U256,
+    pub rules: LiquidationRules,
+    /// Slippage tolerated on the collateral swap
+    pub swap_slippage_bps: u32,
+    /// Minimum expected profit, in bps of the repaid debt
+    pub min_profit_bps: u32,
+}
+
+/// An Aave v3 compatible pool to run an engine instance against
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AavePoolSpec {
+    pub name: String,
+    pub pool_address_provider: Address,
+    pub ui_pool_data: Address,
+    pub flash_liquidator: Address,
+    pub deploy_block: u64,
+    /// Empty means every listed reserve
+    #[serde(default)]
+    pub reserve_allowlist: HashSet<Address>,
+    #[serde(default)]
+    pub reserve_denylist: HashSet<Address>,
+}
+
+impl From<LiquidationCandidate> for LiquidationParams {
+    fn from(value: LiquidationCandidate) -> Self {
+        Self {
+             mode: 0,
+             borrower: value.borrower, 
+             aave_debt_asset: value.debt_asset,
+             aave_collateral: value.collateral_asset, 
+             aave_debt_to_cover: value.debt_to_cover, 
+             morpho_market_id: H256::zero().into(), 
+             morpho_repaid_shares: U256::zero(), 
+             morpho_seized_assets: U256::zero(),
+             compound_collateral: Address::zero(),
+             compound_debt_asset: Address::zero(),
+             compound_debt_to_cover: U256::zero(),
+             compound_min_collateral: U256::zero(), 
+             swap_target: value.swap_target, 
+             swap_allowance_target: value.swap_proxy, 
+             swap_data: value.swap_data,
+             flash_asset: value.debt_asset,
+             min_amt_out: value.min_amt_out
+
+            }
+    }
+}
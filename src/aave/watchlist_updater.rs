@@ -1,14 +1,15 @@
 use std::sync::Arc;
-use anyhow::Result;
-use ethers::{providers::Middleware, types::Address};
+use anyhow::{anyhow, Result};
+use ethers::{contract::LogMeta, providers::Middleware, types::{Address, Filter, U64}};
 use futures_util::{self, StreamExt, stream};
 use tokio::sync::{mpsc, watch};
 
 use super::{
-    abi_bindings::{IAaveV3Pool, IAaveV3PoolEvents},
+    abi_bindings::{IAaveV3Pool, IAaveV3PoolEvents, IPoolAddressesProvider, IPoolConfigurator, ReserveInitializedFilter},
     aave_config::AaveConfig,
     aave_watchlist::AaveWatchList,
     helpers,
+    position_book::PositionBook,
 };
 
 use crate::common::{WatchList, AdminCmd};
@@ -17,8 +18,11 @@ pub struct AaveWatchListUpdater<M: Middleware + 'static> {
     watch_list: Arc<AaveWatchList>,
     pool: Arc<IAaveV3Pool<M>>,
     config: Arc<AaveConfig>,
+    book: Arc<PositionBook>,
     shutdown: watch::Receiver<bool>,
     cmd_rx: mpsc::Receiver<AdminCmd>,
+    /// Timestamp of the last block a reserve update was seen in
+    block_time: Option<(U64, u64)>,
 }
 
 impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
@@ -27,6 +31,7 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
         watch_list: Arc<AaveWatchList>,
         pool: Arc<IAaveV3Pool<M>>,
         config: Arc<AaveConfig>,
+        book: Arc<PositionBook>,
         shutdown: watch::Receiver<bool>,
         cmd_rx: mpsc::Receiver<AdminCmd>,
     ) -> Self {
@@ -34,8 +39,10 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
             watch_list,
             pool,
             config,
+            book,
             shutdown,
             cmd_rx,
+            block_time: None,
         }
     }
 
@@ -50,7 +57,35 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
         tracing::info!("AaveWatchListUpdater started...");
 
         let events = self.pool.events();
-        let mut event_stream = events.stream().await?;
+        let mut event_stream = events.stream_with_meta().await?;
+
+        // New reserves are listed through the PoolConfigurator, not the pool
+        let configurator_addr = IPoolAddressesProvider::new(
+            self.config.pool_address_provider,
+            self.pool.client(),
+        )
+        .get_pool_configurator()
+        .call()
+        .await?;
+
+        let configurator = IPoolConfigurator::new(configurator_addr, self.pool.client());
+        let reserve_init = configurator.reserve_initialized_filter();
+        let mut reserve_stream = reserve_init.stream().await?;
+
+        // aToken transfers move collateral without a pool event. Reserves
+        // listed after startup are picked up on the next restart.
+        let a_tokens: Vec<Address> = self.config.reserves.enabled()
+            .iter()
+            .filter_map(|asset| self.config.reserves.a_token(asset))
+            .collect();
+        let transfer_filter = Filter::new()
+            .address(a_tokens)
+            .event("BalanceTransfer(address,address,uint256,uint256)");
+        let client = self.pool.client();
+        let mut transfer_stream = client.watch(&transfer_filter).await?;
+
+        // Transfers of reserves allowed by a reload are followed after the next restart
+        let mut live = self.config.chain.live.subscribe();
 
         loop {
             tokio::select! {
@@ -61,11 +96,24 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
                     break;
                 }
 
+                // 🔄 Reloaded reserve filters
+                Ok(()) = live.changed() => {
+                    let Some(filter) = live.borrow_and_update().aave_reserve_filters.get(&self.config.name).cloned() else {
+                        continue;
+                    };
+                    self.config.reserves.set_filters(filter.allowlist, filter.denylist);
+                    tracing::info!(
+                        "🔄 Aave pool {} now acts on {} reserves",
+                        self.config.name,
+                        self.config.reserves.enabled().len()
+                    );
+                }
+
                 // 📥 Aave Events
                 evt = event_stream.next() => {
                     match evt {
-                        Some(Ok(event)) => {
-                            self.handle_event(event).await?;
+                        Some(Ok((event, meta))) => {
+                            self.handle_event(event, meta).await?;
                         }
                         Some(Err(e)) => {
                             tracing::error!("Event stream error: {:?}", e);
@@ -77,6 +125,38 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
                     }
                 }
 
+                // 🆕 Reserve listings
+                evt = reserve_stream.next() => {
+                    match evt {
+                        Some(Ok(f)) => self.handle_reserve_initialized(f).await,
+                        Some(Err(e)) => {
+                            tracing::error!("ReserveInitialized stream error: {:?}", e);
+                        }
+                        None => {
+                            tracing::warn!("Aave ReserveInitialized stream ended");
+                            break;
+                        }
+                    }
+                }
+
+                // 🔁 aToken Transfers
+                log = transfer_stream.next() => {
+                    match log {
+                        Some(log) if log.topics.len() == 3 => {
+                            let from = Address::from(log.topics[1]);
+                            let to = Address::from(log.topics[2]);
+
+                            self.book.on_atoken_transfer(from, to);
+                            self.watch_list.mark_dirty(from);
+                        }
+                        Some(_) => {}
+                        None => {
+                            tracing::warn!("aToken transfer stream ended");
+                            break;
+                        }
+                    }
+                }
+
                 // 🧹 Admin Commands
                 cmd = self.cmd_rx.recv() => {
                     match cmd {
@@ -98,7 +178,36 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
         Ok(())
     }
 
-    async fn handle_event(&self, event: IAaveV3PoolEvents) -> Result<()> {
+    /// The pool accrues a reserve up to the timestamp of the block that
+    /// emitted `ReserveDataUpdated`
+    async fn block_timestamp(&mut self, block_number: U64) -> Result<u64> {
+        if let Some((cached, timestamp)) = self.block_time {
+            if cached == block_number {
+                return Ok(timestamp);
+            }
+        }
+
+        let timestamp = self.pool
+            .client()
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| anyhow!("block {} not found", block_number))?
+            .timestamp
+            .as_u64();
+
+        self.block_time = Some((block_number, timestamp));
+        Ok(timestamp)
+    }
+
+    async fn handle_event(&mut self, event: IAaveV3PoolEvents, meta: LogMeta) -> Result<()> {
+        match &event {
+            IAaveV3PoolEvents::ReserveDataUpdatedFilter(f) => {
+                let timestamp = self.block_timestamp(meta.block_number).await?;
+                self.book.on_reserve_updated(f, timestamp);
+            }
+            _ => self.book.apply_event(&event),
+        }
+
         match event {
 
             IAaveV3PoolEvents::BorrowFilter(f) => {
@@ -109,6 +218,7 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
                 self.watch_list
                     .add((f.on_behalf_of, f.reserve))
                     .await?;
+                self.watch_list.mark_dirty(f.on_behalf_of);
 
                 tracing::debug!(
                     "Added borrower {:?} on reserve {:?}",
@@ -131,6 +241,21 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
                 }
                 self.remove_if_no_debt(f.user, f.debt_asset).await?;
             }
+
+            // Collateral-side changes only matter for borrowers we watch
+            IAaveV3PoolEvents::SupplyFilter(f) => {
+                self.watch_list.mark_dirty(f.on_behalf_of);
+            }
+            IAaveV3PoolEvents::WithdrawFilter(f) => {
+                self.watch_list.mark_dirty(f.user);
+            }
+            IAaveV3PoolEvents::ReserveUsedAsCollateralEnabledFilter(f) => {
+                self.watch_list.mark_dirty(f.user);
+            }
+            IAaveV3PoolEvents::ReserveUsedAsCollateralDisabledFilter(f) => {
+                tracing::debug!("Collateral {:?} disabled by {:?}", f.reserve, f.user);
+                self.watch_list.mark_dirty(f.user);
+            }
                 _ => {}
 
         }
@@ -138,6 +263,24 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
         Ok(())
     }
 
+    async fn handle_reserve_initialized(&self, f: ReserveInitializedFilter) {
+        if self.config.reserves.id(&f.asset).is_some() {
+            return;
+        }
+
+        match self.config.reserves.fetch(&self.pool, f.asset).await {
+            Ok(_) => tracing::info!(
+                "🆕 New Aave reserve {:?} listed (tracked: {})",
+                f.asset,
+                self.config.reserves.contains(&f.asset)
+            ),
+            Err(e) => tracing::error!(
+                "Failed to register new Aave reserve {:?}: {:?}",
+                f.asset,
+                e
+            ),
+        }
+    }
 
     async fn prune_watchlist(&self) -> Result<()> {
         
@@ -145,6 +288,11 @@ impl<M: Middleware + Send + Sync + 'static> AaveWatchListUpdater<M> {
 
     tracing::info!("🧹 Pruning {} entries", snapshot.len());
 
+    self.config.reserves.clear_emode_categories();
+
+    // Governance may have changed thresholds since the last load
+    self.book.load_reserves(&self.pool, &self.config).await?;
+
     stream::iter(snapshot)
         .for_each_concurrent(4, |(borrower, reserve)| async move {
             if let Err(e) = self.remove_if_no_debt(borrower, reserve).await {
@@ -5,7 +5,7 @@ use ethers::{types::Address, providers::Middleware};
 
 use crate::{
     common::WatchList, constants, aave::{abi_bindings::{IAaveV3Pool, BorrowFilter, RepayFilter, LiquidationCallFilter},
-        aave_watchlist::AaveWatchList}
+        aave_config::AaveConfig, aave_watchlist::AaveWatchList}
     };
 
 use super::{
@@ -19,7 +19,8 @@ pub struct AaveBootstrap<M> {
     watch_list: Arc<AaveWatchList>,
     state: Arc<BootstrapState>,
     provider: Arc<M>,
-    deploy_block: u64
+    config: Arc<AaveConfig>,
+    cursor: Protocol,
 }
 
 impl<M: Middleware + 'static> AaveBootstrap<M> {
@@ -28,13 +29,22 @@ impl<M: Middleware + 'static> AaveBootstrap<M> {
         watch_list: Arc<AaveWatchList>,
         state: Arc<BootstrapState>,
         provider: Arc<M>,
+        config: Arc<AaveConfig>,
     ) -> Self {
+        // The default pool keeps its original cursor
+        let cursor = if config.name == constants::AAVE_DEFAULT_POOL {
+            Protocol::Aave
+        } else {
+            Protocol::AavePool(config.name.clone())
+        };
+
         Self {
             aave,
             watch_list,
             state,
             provider,
-            deploy_block: constants::AAVE_DEPLOY_BLOCK
+            config,
+            cursor,
         }
     }
 
@@ -99,14 +109,14 @@ impl<M: Middleware + 'static> AaveBootstrap<M> {
 #[async_trait::async_trait]
 impl<M: Middleware + 'static> Bootstrap  for AaveBootstrap<M> {
     async fn run(&self) -> anyhow::Result<()> {
-        tracing::info!("starting aave bootstrap");
-        let whitelist_reserves = &*constants::AAVE_RESERVES;
-        let last_block = self.state.load_last_block(Protocol::Aave).await?;
+        tracing::info!("starting aave bootstrap for pool {}", self.config.name);
+        let whitelist_reserves = &self.config.reserves;
+        let last_block = self.state.load_last_block(self.cursor.clone()).await?;
 
         let latest_block = self.provider.get_block_number().await?.as_u64();
 
         let mut start_block = last_block
-            .unwrap_or(self.deploy_block)
+            .unwrap_or(self.config.deploy_block)
             .saturating_sub(20);
 
         let batch_size = 1_000u64;
@@ -115,7 +125,7 @@ impl<M: Middleware + 'static> Bootstrap  for AaveBootstrap<M> {
              let current_end = (start_block + batch_size).min(latest_block);
              let mut entries: HashSet<(Address, Address)> = HashSet::new();
 
-             tracing::info!("Aave bootstrap scanning {} -> {}", start_block, current_end);
+             tracing::info!("Aave bootstrap [{}] scanning {} -> {}", self.config.name, start_block, current_end);
 
             let (borrows, repays, liqs) = self.fetch_batch(&self.aave, start_block, current_end).await?;
 
@@ -149,13 +159,13 @@ impl<M: Middleware + 'static> Bootstrap  for AaveBootstrap<M> {
                 tracing::info!("Successfully indexed {} new Aave positions", added_count);
             }
 
-            self.state.save_last_block(Protocol::Aave, current_end).await?;
+            self.state.save_last_block(self.cursor.clone(), current_end).await?;
             start_block = current_end + 1;
 
 
         }
 
-        tracing::info!("Aave bootstrap complete");
+        tracing::info!("Aave bootstrap complete for pool {}", self.config.name);
         Ok(())
     }
 
@@ -1,64 +1,127 @@
-use std::sync::Arc;
-use ethers::{types::U256, providers::Middleware};
+use std::{collections::HashSet, sync::Arc};
+use ethers::{types::Address, providers::Middleware};
 
 use crate::{
-    common::WatchList, 
-    constants, 
-    compound::{abi_bindings::IComet, compound_watchlist::CompoundWatchList}, 
+    compound::{
+        abi_bindings::IComet, 
+        borrower_watchlist::CompoundBorrowerWatchList,
+        compound_config::CompoundConfig,
+        compound_watchlist::CompoundWatchList,
+        helpers,
+    }, 
 };
 
-use super::Bootstrap;
+use super::{
+    bootstrap_state::BootstrapState,
+    Bootstrap,
+    Protocol
+};
 
 pub struct CompoundBootstrap<M> {
     pub compound: IComet<M>,
     pub watch_list: Arc<CompoundWatchList>,
+    pub borrowers: Arc<CompoundBorrowerWatchList>,
+    pub state: Arc<BootstrapState>,
+    pub provider: Arc<M>,
+    config: Arc<CompoundConfig>,
+    cursor: Protocol,
 }
 
 impl <M: Middleware + 'static> CompoundBootstrap<M> {
     pub fn new(
         compound: IComet<M>,
         watch_list: Arc<CompoundWatchList>,
+        borrowers: Arc<CompoundBorrowerWatchList>,
+        state: Arc<BootstrapState>,
+        provider: Arc<M>,
+        config: Arc<CompoundConfig>,
     ) -> Self {
         Self {
             compound,
             watch_list,
+            borrowers,
+            state,
+            provider,
+            cursor: config.cursor(),
+            config,
         }
     }
-    
+
+    /// Index accounts touched by `Withdraw` / `Supply` / `AbsorbDebt` and
+    /// keep the ones that still borrow
+    async fn index_borrowers(&self) -> anyhow::Result<()> {
+        let latest_block = self.provider.get_block_number().await?.as_u64();
+        let mut start_block = self
+            .state
+            .load_last_block(self.cursor.clone())
+            .await?
+            .unwrap_or(self.config.deploy_block)
+            .saturating_sub(20);
+
+        let batch_size = 2_000u64;
+
+        while start_block <= latest_block {
+            let current_end = (start_block + batch_size).min(latest_block);
+
+            tracing::info!("Compound {} borrower bootstrap scanning {} -> {}", self.config.name, start_block, current_end);
+
+            let withdraw_filter = self.compound.withdraw_filter().from_block(start_block).to_block(current_end);
+            let supply_filter = self.compound.supply_filter().from_block(start_block).to_block(current_end);
+            let absorb_filter = self.compound.absorb_debt_filter().from_block(start_block).to_block(current_end);
+
+            let (withdraws, supplies, absorbs) = tokio::try_join!(
+                withdraw_filter.query(),
+                supply_filter.query(),
+                absorb_filter.query(),
+            )?;
+
+            let touched: HashSet<Address> = withdraws
+                .into_iter()
+                .map(|ev| ev.src)
+                .chain(supplies.into_iter().map(|ev| ev.dst))
+                .chain(absorbs.into_iter().map(|ev| ev.borrower))
+                .collect();
+
+            if !touched.is_empty() {
+                helpers::refresh_borrowers(&self.compound, &self.borrowers, touched).await?;
+            }
+
+            self.state.save_last_block(self.cursor.clone(), current_end).await?;
+            start_block = current_end + 1;
+        }
+
+        tracing::info!(
+            "Compound {} borrower bootstrap complete: {} borrowers",
+            self.config.name,
+            self.borrowers.snapshot().len()
+        );
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
 impl<M: Middleware + 'static> Bootstrap for CompoundBootstrap<M> {
     async fn run(&self) -> anyhow::Result<()> {
-        tracing::info!("Starting Compound Buy-Collateral Bootstrap");
-
-        // Use the constants for the specific collateral assets supported by this Comet instance
-        let assets = &*constants::COMPOUND_RESERVES;
-
-        for &asset in assets {
-            // Check protocol inventory
-            match self.compound.get_collateral_reserves(asset).await {
-                Ok(reserves) if reserves > U256::zero() => {
-                    tracing::info!(
-                        "Asset {:?} has {:?} available in reserves", 
-                        asset, 
-                        reserves
-                    );
-                    self.watch_list.add((asset, reserves)).await?;
-                }
-                Ok(_) => tracing::debug!("No reserves for asset {:?}", asset),
-                Err(e) => tracing::error!("Failed to fetch reserves for {:?}: {}", asset, e),
-            }
+        tracing::info!("Starting Compound Buy-Collateral Bootstrap for {}", self.config.name);
+
+        // Collateral assets listed on this Comet, booked at their on-chain reserves
+        let assets = helpers::collateral_assets(&self.compound).await?;
+        let reserves = helpers::reconcile_reserves(
+            &self.compound,
+            &self.watch_list,
+            assets.into_iter().map(|info| info.asset),
+        ).await?;
+
+        for (asset, amount) in reserves {
+            tracing::info!("Asset {:?} has {:?} available in reserves", asset, amount);
         }
 
         tracing::info!("Compound Buy-Collateral bootstrap complete");
-        Ok(())
+
+        self.index_borrowers().await
     }
     
     fn name(&self) -> &'static str {
         "Compound"
     }
 }
-  
-
-     
@@ -1,6 +1,7 @@
 pub mod aave_bootstrap;
 pub mod morpho_bootstrap;
 pub mod compound_bootstrap;
+pub mod pre_liquidation_bootstrap;
 pub mod bootstrap_state;
 
 use std::sync::Arc;
@@ -13,11 +14,21 @@ pub trait Bootstrap: Send + Sync {
 }
 
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// Bootstrap cursor key. Variants are bincode-encoded by index, so new
+/// ones go at the end.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Protocol {
     Morpho,
     Aave,
     Compound,
+    /// Extra Aave-compatible pool, by name
+    AavePool(String),
+    /// Morpho `CreateMarket` indexing
+    MorphoMarkets,
+    /// Morpho pre-liquidation contracts and authorizations
+    MorphoPreLiquidation,
+    /// Extra Comet market, by name
+    CometMarket(String),
 }
 
 pub struct BootstrapExecutor {
@@ -3,8 +3,8 @@ use tokio::time::{sleep, Duration};
 use ethers::{types::{H256, Address}, providers::Middleware};
 
 use crate::{
-    common::WatchList, constants, morpho::{abi_bindings::{IMorphoBlue, BorrowFilter, RepayFilter, LiquidateFilter}, 
-        morpho_watchlist::MorphoWatchList}
+    common::WatchList, morpho::{abi_bindings::{IMorphoBlue, BorrowFilter, RepayFilter, LiquidateFilter}, 
+        market_registry::{MarketInfo, MarketRegistry}, morpho_watchlist::MorphoWatchList}
     };
 
 use super::{
@@ -16,6 +16,7 @@ use super::{
 pub struct MorphoBootstrap<M> {
     morpho: IMorphoBlue<M>,
     watch_list: Arc<MorphoWatchList>,
+    markets: Arc<MarketRegistry>,
     state: Arc<BootstrapState>,
     provider: Arc<M>,
     deploy_block: u64,
@@ -27,15 +28,18 @@ impl<M: Middleware + 'static> MorphoBootstrap<M> {
     pub fn new(
         morpho: IMorphoBlue<M>,
         watch_list: Arc<MorphoWatchList>,
+        markets: Arc<MarketRegistry>,
         state: Arc<BootstrapState>,
         provider: Arc<M>,
+        deploy_block: u64,
     ) -> Self {
         Self {
             morpho,
             watch_list,
+            markets,
             state,
             provider,
-            deploy_block: constants::MORPHO_DEPLOY_BLOCK
+            deploy_block
         }
     }
 
@@ -95,27 +99,68 @@ impl<M: Middleware + 'static> MorphoBootstrap<M> {
 
         }
 
-}
 
-#[async_trait::async_trait]
-impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
+    /// Index `CreateMarket` events into the market registry
+    async fn index_markets(&self, latest_block: u64) -> anyhow::Result<()> {
+        let mut start_block = self
+            .state
+            .load_last_block(Protocol::MorphoMarkets)
+            .await?
+            .unwrap_or(self.deploy_block)
+            .saturating_sub(20);
 
-    async fn run(&self) -> anyhow::Result<()> {
-        tracing::info!("Starting Morpho Bootstrap");
+        let batch_size = 10_000u64;
+        let mut discovered = 0;
 
-        let whitelist_markets = &*constants::MORPHO_MARKETS;
-        let last_block = self.state.load_last_block(Protocol::Morpho).await?;
+        while start_block <= latest_block {
+            let current_end = (start_block + batch_size).min(latest_block);
 
-        let latest_block = self.provider.get_block_number().await?.as_u64();
+            let logs = self
+                .morpho
+                .create_market_filter()
+                .from_block(start_block)
+                .to_block(current_end)
+                .query_with_meta()
+                .await?;
+
+            for (ev, meta) in logs {
+                let info = MarketInfo {
+                    loan_token: ev.market_params.loan_token,
+                    collateral_token: ev.market_params.collateral_token,
+                    oracle: ev.market_params.oracle,
+                    irm: ev.market_params.irm,
+                    lltv: ev.market_params.lltv,
+                    created_block: meta.block_number.as_u64(),
+                };
+                if self.markets.register(H256::from(ev.id), info).await? {
+                    discovered += 1;
+                }
+            }
 
-        let mut start_block = last_block
-            .unwrap_or(self.deploy_block)
-            .saturating_sub(20);
+            self.state.save_last_block(Protocol::MorphoMarkets, current_end).await?;
+            start_block = current_end + 1;
+        }
+
+        if discovered > 0 {
+            tracing::info!("🗂️ Discovered {} new Morpho markets", discovered);
+        }
 
+        Ok(())
+    }
+
+    /// Index borrowers of `markets` over [start_block, end_block]
+    async fn scan_borrowers(
+        &self,
+        start_block: u64,
+        end_block: u64,
+        markets: &HashSet<H256>,
+        cursor: Option<Protocol>,
+    ) -> anyhow::Result<()> {
         let batch_size = 2_000u64;
-        
-        while  start_block <= latest_block {
-            let current_end = (start_block + batch_size).min(latest_block);
+        let mut start_block = start_block;
+
+        while  start_block <= end_block {
+            let current_end = (start_block + batch_size).min(end_block);
             let mut entries: HashSet<(Address, H256)> = HashSet::new();
             
             tracing::info!("Morpho bootstrap scanning {} -> {}", start_block, current_end);
@@ -129,7 +174,7 @@ impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
             for ev in borrows.into_iter() {
                 let market_id = H256::from(ev.id);
 
-                if whitelist_markets.contains(&market_id) {
+                if markets.contains(&market_id) {
                     entries.insert((ev.on_behalf, market_id));
                 }
             }
@@ -137,7 +182,7 @@ impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
             for ev in repays.into_iter() {
                 let market_id = H256::from(ev.id);
 
-                if whitelist_markets.contains(&market_id) {
+                if markets.contains(&market_id) {
                     entries.insert((ev.on_behalf, market_id));
                 }
             }
@@ -145,7 +190,7 @@ impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
             for ev in liqs.into_iter() {
                  let market_id = H256::from(ev.id);
 
-                 if whitelist_markets.contains(&market_id) {
+                 if markets.contains(&market_id) {
                     entries.insert((ev.borrower, market_id));
                 }
 
@@ -165,11 +210,53 @@ impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
                 tracing::info!("Successfully indexed {} new Morpho positions", added_count);
              }
 
-            self.state.save_last_block(Protocol::Morpho, current_end).await?;
+            if let Some(cursor) = cursor.clone() {
+                self.state.save_last_block(cursor, current_end).await?;
+            }
             start_block = current_end + 1;
 
         }
 
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
+
+    async fn run(&self) -> anyhow::Result<()> {
+        tracing::info!("Starting Morpho Bootstrap");
+
+        let latest_block = self.provider.get_block_number().await?.as_u64();
+
+        self.index_markets(latest_block).await?;
+        self.markets.refresh_selection(&self.morpho).await?;
+
+        let selected = self.markets.selected();
+        let last_block = self.state.load_last_block(Protocol::Morpho).await?;
+
+        // Markets selected since the last run have no history below the cursor
+        if let Some(last_block) = last_block {
+            let fresh = self.markets.unindexed();
+            let from = fresh
+                .iter()
+                .filter_map(|id| self.markets.info(id))
+                .map(|info| info.created_block)
+                .min();
+
+            if let Some(from) = from {
+                tracing::info!("Backfilling {} newly selected Morpho markets from block {}", fresh.len(), from);
+                self.scan_borrowers(from, last_block, &fresh, None).await?;
+            }
+        }
+
+        let start_block = last_block
+            .unwrap_or(self.deploy_block)
+            .saturating_sub(20);
+
+        self.scan_borrowers(start_block, latest_block, &selected, Some(Protocol::Morpho)).await?;
+        self.markets.mark_indexed(&selected).await?;
+
         tracing::info!("Morpho bootstrap complete");
 
         Ok(())    
@@ -180,5 +267,3 @@ impl<M: Middleware + 'static> Bootstrap for MorphoBootstrap<M>  {
         "morpho"
     }
 }
-
-   
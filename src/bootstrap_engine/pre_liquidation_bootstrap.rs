@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use ethers::providers::Middleware;
+
+use crate::morpho::{
+    abi_bindings::{IMorphoBlue, IPreLiquidationFactory},
+    pre_liquidation::PreLiquidationRegistry,
+};
+
+use super::{
+    bootstrap_state::BootstrapState,
+    Bootstrap,
+    Protocol
+};
+
+/// Indexes Morpho pre-liquidation contracts, then the borrowers that
+/// authorized them. Both share one cursor, so a contract is always known
+/// before its authorizations are applied.
+pub struct PreLiquidationBootstrap<M> {
+    factory: IPreLiquidationFactory<M>,
+    morpho: IMorphoBlue<M>,
+    registry: Arc<PreLiquidationRegistry>,
+    state: Arc<BootstrapState>,
+    provider: Arc<M>,
+    deploy_block: u64,
+}
+
+impl<M: Middleware + 'static> PreLiquidationBootstrap<M> {
+    pub fn new(
+        factory: IPreLiquidationFactory<M>,
+        morpho: IMorphoBlue<M>,
+        registry: Arc<PreLiquidationRegistry>,
+        state: Arc<BootstrapState>,
+        provider: Arc<M>,
+        deploy_block: u64,
+    ) -> Self {
+        Self {
+            factory,
+            morpho,
+            registry,
+            state,
+            provider,
+            deploy_block,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> Bootstrap for PreLiquidationBootstrap<M> {
+
+    async fn run(&self) -> anyhow::Result<()> {
+        tracing::info!("Starting Morpho pre-liquidation bootstrap");
+
+        let latest_block = self.provider.get_block_number().await?.as_u64();
+        let mut start_block = self
+            .state
+            .load_last_block(Protocol::MorphoPreLiquidation)
+            .await?
+            .unwrap_or(self.deploy_block)
+            .saturating_sub(20);
+
+        let batch_size = 5_000u64;
+
+        while start_block <= latest_block {
+            let current_end = (start_block + batch_size).min(latest_block);
+
+            tracing::info!("Morpho pre-liquidation bootstrap scanning {} -> {}", start_block, current_end);
+
+            let created_filter = self
+                .factory
+                .create_pre_liquidation_filter()
+                .from_block(start_block)
+                .to_block(current_end);
+
+            let auth_filter = self
+                .morpho
+                .set_authorization_filter()
+                .from_block(start_block)
+                .to_block(current_end);
+
+            let (created, auths) = tokio::try_join!(
+                created_filter.query(),
+                auth_filter.query_with_meta(),
+            )?;
+
+            for ev in created {
+                self.registry.on_created(ev).await?;
+            }
+
+            let mut auths = auths;
+            auths.sort_by_key(|(_, meta)| (meta.block_number, meta.log_index));
+            for (ev, _) in auths {
+                self.registry.on_authorization(ev).await?;
+            }
+
+            self.state.save_last_block(Protocol::MorphoPreLiquidation, current_end).await?;
+            start_block = current_end + 1;
+        }
+
+        tracing::info!("Morpho pre-liquidation bootstrap complete");
+
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "morpho_pre_liquidation"
+    }
+}
@@ -0,0 +1,490 @@
+use std::{collections::HashSet, env, sync::Arc};
+
+use anyhow::{anyhow, bail, Context};
+use ethers::{
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{Address, H256, U256},
+    utils::parse_ether,
+};
+use serde::Deserialize;
+use tokio::sync::watch;
+
+use crate::{
+    aave::types::AavePoolSpec,
+    compound::types::CometSpec,
+    constants,
+    settings::{ChainSection, LiveSettings, ReserveFilter, Settings, Tuning},
+};
+
+/// Everything chain specific one set of engines runs against
+#[derive(Debug)]
+pub struct ChainProfile {
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+    pub rpc_url_http: String,
+    pub flash_liquidator: Address,
+    /// Off-ramp receiving the stablecoin share of distributed profits
+    pub breet: Option<Address>,
+    pub sled_path: String,
+    pub gas_threshold: U256,
+    pub refuel_amount: U256,
+    pub tokens: ChainTokens,
+    /// Reloadable settings, engines subscribe to pick up changes
+    pub live: watch::Sender<LiveSettings>,
+    pub aave: Option<AaveDeployment>,
+    pub morpho: Option<MorphoDeployment>,
+    pub compound: Option<CompoundDeployment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainTokens {
+    pub usdc: Address,
+    pub usdt: Address,
+    /// Wrapped native token, kept in the flash liquidator to refuel gas
+    pub wrapped_native: Address,
+    /// Defaults to USDC, USDT and the wrapped native token
+    #[serde(default)]
+    pub profit_assets: Vec<Address>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AaveDeployment {
+    pub pool: Address,
+    pub oracle: Address,
+    pub pool_address_provider: Address,
+    pub ui_pool_data: Address,
+    pub deploy_block: u64,
+    /// Empty means every listed reserve
+    #[serde(default)]
+    pub reserve_allowlist: HashSet<Address>,
+    #[serde(default)]
+    pub reserve_denylist: HashSet<Address>,
+    #[serde(default)]
+    pub extra_pools: Vec<AavePoolSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MorphoDeployment {
+    pub morpho_blue: Address,
+    pub deploy_block: u64,
+    /// Defaults to USDC and USDT
+    #[serde(default)]
+    pub loan_tokens: HashSet<Address>,
+    /// Empty means any collateral token
+    #[serde(default)]
+    pub collateral_tokens: HashSet<Address>,
+    /// Market ids to act on, empty means every market matching the rules
+    #[serde(default)]
+    pub markets: HashSet<H256>,
+    #[serde(default)]
+    pub min_lltv_bps: u64,
+    #[serde(default = "default_max_lltv_bps")]
+    pub max_lltv_bps: u64,
+    /// Whole loan tokens
+    #[serde(default = "default_min_total_borrow")]
+    pub min_total_borrow: u64,
+    pub pre_liquidation_factory: Option<Address>,
+    /// Defaults to the Morpho deploy block
+    #[serde(default)]
+    pub pre_liquidation_factory_block: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompoundDeployment {
+    pub comet: Address,
+    pub deploy_block: u64,
+    #[serde(default)]
+    pub extra_comets: Vec<CometSpec>,
+}
+
+impl AaveDeployment {
+    /// Allow/deny lists of the default and every extra pool, by pool name
+    fn reserve_filters(&self) -> Vec<(String, ReserveFilter)> {
+        let default = ReserveFilter {
+            allowlist: self.reserve_allowlist.clone(),
+            denylist: self.reserve_denylist.clone(),
+        };
+        let extra = self.extra_pools.iter().map(|pool| {
+            let filter = ReserveFilter {
+                allowlist: pool.reserve_allowlist.clone(),
+                denylist: pool.reserve_denylist.clone(),
+            };
+            (pool.name.clone(), filter)
+        });
+
+        std::iter::once((constants::AAVE_DEFAULT_POOL.to_string(), default)).chain(extra).collect()
+    }
+}
+
+fn default_max_lltv_bps() -> u64 {
+    10_000
+}
+
+fn default_min_total_borrow() -> u64 {
+    10_000
+}
+
+impl ChainProfile {
+    /// Polygon PoS, from the built-in addresses and the unprefixed env vars
+    pub fn polygon(settings: &Settings) -> anyhow::Result<Self> {
+        // USDC and USDT unless set
+        let loan_tokens = match constants::load_address_list("MORPHO_LOAN_TOKENS")? {
+            tokens if tokens.is_empty() => [*constants::USDC, *constants::USDT].into_iter().collect(),
+            tokens => tokens,
+        };
+
+        Self {
+            name: constants::DEFAULT_CHAIN.to_string(),
+            chain_id: constants::CHAIN_ID,
+            rpc_url: constants::RPC_URL.clone(),
+            rpc_url_http: constants::RPC_URL_HTTP.clone(),
+            flash_liquidator: *constants::FLASH_LIQUIDATOR,
+            breet: Some(*constants::BREET),
+            sled_path: settings.sled_path.clone(),
+            gas_threshold: parse_ether(constants::GAS_THRESHOLD)?,
+            refuel_amount: parse_ether(constants::REFUEL_AMOUNT)?,
+            tokens: ChainTokens {
+                usdc: *constants::USDC,
+                usdt: *constants::USDT,
+                wrapped_native: *constants::WPOL,
+                profit_assets: constants::PROFIT_DIST_ASSETS.clone(),
+            },
+            live: watch::Sender::new(LiveSettings::default()),
+            aave: Some(AaveDeployment {
+                pool: *constants::AAVE_V3_POOL,
+                oracle: *constants::AAVE_ORACLE,
+                pool_address_provider: *constants::POOL_ADDRESS_PROVIDER,
+                ui_pool_data: *constants::UIPOOL_DATA_PROVIDER,
+                deploy_block: constants::AAVE_DEPLOY_BLOCK,
+                reserve_allowlist: constants::load_address_list("AAVE_RESERVE_ALLOWLIST")?,
+                reserve_denylist: constants::load_address_list("AAVE_RESERVE_DENYLIST")?,
+                extra_pools: constants::load_aave_pools("AAVE_EXTRA_POOLS")?,
+            }),
+            morpho: Some(MorphoDeployment {
+                morpho_blue: *constants::MORPHO_BLUE,
+                deploy_block: constants::MORPHO_DEPLOY_BLOCK,
+                loan_tokens,
+                collateral_tokens: constants::load_address_list("MORPHO_COLLATERAL_TOKENS")?,
+                markets: HashSet::new(),
+                min_lltv_bps: constants::load_u64("MORPHO_MIN_LLTV_BPS", 0)?,
+                max_lltv_bps: constants::load_u64("MORPHO_MAX_LLTV_BPS", default_max_lltv_bps())?,
+                min_total_borrow: constants::load_u64("MORPHO_MIN_TOTAL_BORROW", default_min_total_borrow())?,
+                pre_liquidation_factory: constants::load_optional_address("MORPHO_PRE_LIQUIDATION_FACTORY")?,
+                pre_liquidation_factory_block: constants::load_u64(
+                    "MORPHO_PRE_LIQUIDATION_FACTORY_BLOCK",
+                    constants::MORPHO_DEPLOY_BLOCK,
+                )?,
+            }),
+            compound: Some(CompoundDeployment {
+                comet: *constants::COMET_USDT,
+                deploy_block: constants::COMPOUND_DEPLOY_BLOCK,
+                extra_comets: constants::load_comets("COMPOUND_EXTRA_COMETS")?,
+            }),
+        }
+        .checked(settings.tuning.clone())
+    }
+
+    /// Chain `name` from its `[chains.<name>]` table. RPC endpoints, the
+    /// flash liquidator and Breet can be overridden from the env.
+    pub fn from_section(name: &str, section: &ChainSection, settings: &Settings) -> anyhow::Result<Self> {
+        let var = |key: &str| env_var_name(name, key);
+        let endpoint = |key: &str, configured: &Option<String>| {
+            env::var(var(key))
+                .ok()
+                .or_else(|| configured.clone())
+                .ok_or_else(|| anyhow!("{}: neither {} nor the config file sets {}", name, var(key), key.to_lowercase()))
+        };
+
+        let mut flash_liquidator = section.flash_liquidator;
+        crate::settings::override_with(&mut flash_liquidator, &var("FLASH_LIQUIDATOR"))?;
+        let mut breet = section.breet;
+        if let Ok(raw) = env::var(var("BREET")) {
+            breet = Some(raw.trim().parse().with_context(|| format!("invalid address in {}", var("BREET")))?);
+        }
+
+        Self {
+            name: name.to_string(),
+            chain_id: section.chain_id,
+            rpc_url: endpoint("RPC_URL", &section.rpc_url)?,
+            rpc_url_http: endpoint("RPC_URL_HTTP", &section.rpc_url_http)?,
+            flash_liquidator,
+            breet,
+            sled_path: sled_path(name, settings),
+            gas_threshold: parse_ether(section.gas_threshold)?,
+            refuel_amount: parse_ether(section.refuel_amount)?,
+            tokens: section.tokens.clone(),
+            live: watch::Sender::new(LiveSettings::default()),
+            aave: section.aave.clone(),
+            morpho: section.morpho.clone(),
+            compound: section.compound.clone(),
+        }
+        .checked(settings.tuning.clone())
+    }
+
+    /// Chain `name` from its `<NAME>_*` env vars. A protocol is deployed
+    /// when its main address is set (`_AAVE_POOL`, `_MORPHO_BLUE`, `_COMET`).
+    pub fn from_env(name: &str, settings: &Settings) -> anyhow::Result<Self> {
+        let var = |key: &str| env_var_name(name, key);
+        let required = |key: &str| env::var(var(key)).with_context(|| format!("{} is not set", var(key)));
+        let address = |key: &str| -> anyhow::Result<Address> {
+            required(key)?.trim().parse().with_context(|| format!("invalid address in {}", var(key)))
+        };
+        let number = |key: &str| -> anyhow::Result<u64> {
+            required(key)?.trim().parse().with_context(|| format!("invalid integer in {}", var(key)))
+        };
+        let is_set = |key: &str| env::var(var(key)).is_ok();
+
+        let tokens = ChainTokens {
+            usdc: address("USDC")?,
+            usdt: address("USDT")?,
+            wrapped_native: address("WRAPPED_NATIVE")?,
+            profit_assets: constants::load_address_list(&var("PROFIT_ASSETS"))?.into_iter().collect(),
+        };
+
+        let aave = if is_set("AAVE_POOL") {
+            Some(AaveDeployment {
+                pool: address("AAVE_POOL")?,
+                oracle: address("AAVE_ORACLE")?,
+                pool_address_provider: address("AAVE_POOL_ADDRESS_PROVIDER")?,
+                ui_pool_data: address("AAVE_UI_POOL_DATA")?,
+                deploy_block: number("AAVE_DEPLOY_BLOCK")?,
+                reserve_allowlist: constants::load_address_list(&var("AAVE_RESERVE_ALLOWLIST"))?,
+                reserve_denylist: constants::load_address_list(&var("AAVE_RESERVE_DENYLIST"))?,
+                extra_pools: constants::load_aave_pools(&var("AAVE_EXTRA_POOLS"))?,
+            })
+        } else {
+            None
+        };
+
+        let morpho = if is_set("MORPHO_BLUE") {
+            let pre_liquidation_factory = if is_set("MORPHO_PRE_LIQUIDATION_FACTORY") {
+                Some(address("MORPHO_PRE_LIQUIDATION_FACTORY")?)
+            } else {
+                None
+            };
+
+            Some(MorphoDeployment {
+                morpho_blue: address("MORPHO_BLUE")?,
+                deploy_block: number("MORPHO_DEPLOY_BLOCK")?,
+                loan_tokens: constants::load_address_list(&var("MORPHO_LOAN_TOKENS"))?,
+                collateral_tokens: constants::load_address_list(&var("MORPHO_COLLATERAL_TOKENS"))?,
+                markets: HashSet::new(),
+                min_lltv_bps: constants::load_u64(&var("MORPHO_MIN_LLTV_BPS"), 0)?,
+                max_lltv_bps: constants::load_u64(&var("MORPHO_MAX_LLTV_BPS"), default_max_lltv_bps())?,
+                min_total_borrow: constants::load_u64(&var("MORPHO_MIN_TOTAL_BORROW"), default_min_total_borrow())?,
+                pre_liquidation_factory,
+                pre_liquidation_factory_block: constants::load_u64(&var("MORPHO_PRE_LIQUIDATION_FACTORY_BLOCK"), 0)?,
+            })
+        } else {
+            None
+        };
+
+        let compound = if is_set("COMET") {
+            Some(CompoundDeployment {
+                comet: address("COMET")?,
+                deploy_block: number("COMPOUND_DEPLOY_BLOCK")?,
+                extra_comets: constants::load_comets(&var("COMPOUND_EXTRA_COMETS"))?,
+            })
+        } else {
+            None
+        };
+
+        Self {
+            name: name.to_string(),
+            chain_id: number("CHAIN_ID")?,
+            rpc_url: required("RPC_URL")?,
+            rpc_url_http: required("RPC_URL_HTTP")?,
+            flash_liquidator: address("FLASH_LIQUIDATOR")?,
+            breet: if is_set("BREET") { Some(address("BREET")?) } else { None },
+            sled_path: sled_path(name, settings),
+            gas_threshold: parse_ether(constants::load_u64(&var("GAS_THRESHOLD"), constants::GAS_THRESHOLD)?)?,
+            refuel_amount: parse_ether(constants::load_u64(&var("REFUEL_AMOUNT"), constants::REFUEL_AMOUNT)?)?,
+            tokens,
+            live: watch::Sender::new(LiveSettings::default()),
+            aave,
+            morpho,
+            compound,
+        }
+        .checked(settings.tuning.clone())
+    }
+
+    /// Fill the defaults a config file or env may leave out and reject
+    /// inconsistent values
+    fn checked(mut self, tuning: Tuning) -> anyhow::Result<Self> {
+        let tokens = &mut self.tokens;
+        if tokens.profit_assets.is_empty() {
+            tokens.profit_assets = vec![tokens.usdc, tokens.usdt, tokens.wrapped_native];
+        }
+
+        if let Some(morpho) = self.morpho.as_mut() {
+            if morpho.loan_tokens.is_empty() {
+                morpho.loan_tokens = [tokens.usdc, tokens.usdt].into_iter().collect();
+            }
+            if morpho.pre_liquidation_factory_block == 0 {
+                morpho.pre_liquidation_factory_block = morpho.deploy_block;
+            }
+            if morpho.min_lltv_bps > morpho.max_lltv_bps || morpho.max_lltv_bps > 10_000 {
+                bail!("{}: Morpho LLTV bounds must satisfy min <= max <= 10000", self.name);
+            }
+        }
+
+        if let Some(aave) = &self.aave {
+            if aave.extra_pools.iter().any(|pool| pool.name == constants::AAVE_DEFAULT_POOL) {
+                bail!("{}: {} is reserved for the default pool", self.name, constants::AAVE_DEFAULT_POOL);
+            }
+        }
+
+        if let Some(compound) = &self.compound {
+            if compound.extra_comets.iter().any(|comet| comet.name == constants::COMPOUND_DEFAULT_MARKET) {
+                bail!("{}: {} is reserved for the default Comet", self.name, constants::COMPOUND_DEFAULT_MARKET);
+            }
+        }
+
+        if self.refuel_amount < self.gas_threshold {
+            bail!("{}: refuel amount is below the gas threshold", self.name);
+        }
+
+        let live = LiveSettings {
+            tuning,
+            aave_reserve_filters: self.aave.iter().flat_map(AaveDeployment::reserve_filters).collect(),
+            morpho_loan_tokens: self.morpho.iter().flat_map(|morpho| morpho.loan_tokens.clone()).collect(),
+            morpho_collateral_tokens: self.morpho.iter().flat_map(|morpho| morpho.collateral_tokens.clone()).collect(),
+            morpho_markets: self.morpho.iter().flat_map(|morpho| morpho.markets.clone()).collect(),
+        };
+        self.live.send_replace(live);
+
+        Ok(self)
+    }
+
+    /// Current tunables
+    pub fn tuning(&self) -> Tuning {
+        self.live.borrow().tuning.clone()
+    }
+
+    /// The original Polygon deployment keeps its sled path
+    pub fn is_default(&self) -> bool {
+        self.name == constants::DEFAULT_CHAIN
+    }
+
+    /// Keeper wallet signing for this chain
+    pub fn wallet(&self) -> LocalWallet {
+        constants::WALLET.clone().with_chain_id(self.chain_id)
+    }
+
+    /// Check the RPC serves this chain and every configured contract has
+    /// code, so a wrong address fails at startup instead of on first use
+    pub async fn verify<M: Middleware + 'static>(&self, client: Arc<M>) -> anyhow::Result<()> {
+        let chain_id = client.get_chainid().await?.as_u64();
+        if chain_id != self.chain_id {
+            bail!("{} RPC serves chain {}, expected {}", self.name, chain_id, self.chain_id);
+        }
+
+        let mut missing = Vec::new();
+        for (label, address) in self.contracts() {
+            if client.get_code(address, None).await?.is_empty() {
+                missing.push(format!("{} ({:?})", label, address));
+            }
+        }
+
+        if !missing.is_empty() {
+            bail!("no contract code on {} at {}", self.name, missing.join(", "));
+        }
+
+        tracing::info!("✅ {} profile verified against chain {}", self.name, chain_id);
+        Ok(())
+    }
+
+    fn contracts(&self) -> Vec<(String, Address)> {
+        let mut contracts = vec![
+            ("flash_liquidator".to_string(), self.flash_liquidator),
+            ("tokens.usdc".to_string(), self.tokens.usdc),
+            ("tokens.usdt".to_string(), self.tokens.usdt),
+            ("tokens.wrapped_native".to_string(), self.tokens.wrapped_native),
+        ];
+
+        if let Some(aave) = &self.aave {
+            contracts.push(("aave.pool".to_string(), aave.pool));
+            contracts.push(("aave.oracle".to_string(), aave.oracle));
+            contracts.push(("aave.pool_address_provider".to_string(), aave.pool_address_provider));
+            contracts.push(("aave.ui_pool_data".to_string(), aave.ui_pool_data));
+            for pool in &aave.extra_pools {
+                contracts.push((format!("aave.{}.pool_address_provider", pool.name), pool.pool_address_provider));
+                contracts.push((format!("aave.{}.ui_pool_data", pool.name), pool.ui_pool_data));
+                contracts.push((format!("aave.{}.flash_liquidator", pool.name), pool.flash_liquidator));
+            }
+        }
+
+        if let Some(morpho) = &self.morpho {
+            contracts.push(("morpho.morpho_blue".to_string(), morpho.morpho_blue));
+            if let Some(factory) = morpho.pre_liquidation_factory {
+                contracts.push(("morpho.pre_liquidation_factory".to_string(), factory));
+            }
+        }
+
+        if let Some(compound) = &self.compound {
+            contracts.push(("compound.comet".to_string(), compound.comet));
+            for comet in &compound.extra_comets {
+                contracts.push((format!("compound.{}.comet", comet.name), comet.comet));
+                contracts.push((format!("compound.{}.flash_liquidator", comet.name), comet.flash_liquidator));
+            }
+        }
+
+        contracts
+    }
+}
+
+/// The default chain reads the unprefixed vars, any other chain `<NAME>_*`
+fn env_var_name(chain: &str, key: &str) -> String {
+    if chain == constants::DEFAULT_CHAIN {
+        key.to_string()
+    } else {
+        format!("{}_{}", chain.to_uppercase(), key)
+    }
+}
+
+fn sled_path(chain: &str, settings: &Settings) -> String {
+    if chain == constants::DEFAULT_CHAIN {
+        settings.sled_path.clone()
+    } else {
+        format!("{}_{}", settings.sled_path, chain)
+    }
+}
+
+/// Polygon, every `[chains.*]` table of the config file and every chain
+/// listed in `CHAINS`
+pub fn load_profiles(settings: &Settings) -> anyhow::Result<Vec<Arc<ChainProfile>>> {
+    let mut profiles = vec![match settings.chains.get(constants::DEFAULT_CHAIN) {
+        Some(section) => ChainProfile::from_section(constants::DEFAULT_CHAIN, section, settings)?,
+        None => ChainProfile::polygon(settings)?,
+    }];
+
+    for (name, section) in &settings.chains {
+        if name != constants::DEFAULT_CHAIN {
+            profiles.push(ChainProfile::from_section(name, section, settings)?);
+        }
+    }
+
+    for name in env::var("CHAINS").unwrap_or_default().split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if name == constants::DEFAULT_CHAIN {
+            return Err(anyhow!("{} is always enabled, remove it from CHAINS", name));
+        }
+        if !settings.chains.contains_key(name) {
+            profiles.push(ChainProfile::from_env(name, settings)?);
+        }
+    }
+
+    let mut chain_ids = HashSet::new();
+    for profile in &profiles {
+        if !chain_ids.insert(profile.chain_id) {
+            bail!("chain id {} is used by more than one chain", profile.chain_id);
+        }
+    }
+
+    Ok(profiles.into_iter().map(Arc::new).collect())
+}
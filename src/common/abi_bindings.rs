@@ -20,6 +20,15 @@ abigen!(
         function balanceOf(address account) external view returns (uint256)
         function decimals() external view returns (uint8)
         function symbol() external view returns (string)
+        function approve(address spender, uint256 amount) external returns (bool)
+        function allowance(address owner, address spender) external view returns (uint256)
+    ]"#
+);
+
+abigen!(
+    IPriceFeed,
+    r#"[
+        function aggregator() external view returns (address)
     ]"#
 );
 
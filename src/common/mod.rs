@@ -1,6 +1,6 @@
 pub mod abi_bindings;
-pub mod liq_data;
 pub mod paraswap;
+pub mod plugin;
 pub mod task_manager;
 pub mod simulation_sandbox;
 
@@ -8,25 +8,35 @@ pub mod simulation_sandbox;
 use ethers::{
     providers::Middleware,
     signers::Signer,
-    types::{Address, Bytes, H256 as TxHash, U256}
+    types::{Address, Bytes, TransactionReceipt, H256 as TxHash, U256}
 };
 
 use std::sync::Arc;
 
 use crate::{
-    aave::{aave_watchlist::AaveWatchList, abi_bindings::{IAaveV3Pool, AaveOracle, UiPoolDataProvider}},
-    bootstrap_engine::bootstrap_state::BootstrapState,
-    common::{abi_bindings::{IERC20, IFlashLiquidator, LiquidationParams}, simulation_sandbox::{AnvilSandbox, SimResult}},
-    compound::{abi_bindings::IComet, compound_watchlist::CompoundWatchList},
+    chain_profile::ChainProfile,
+    common::{abi_bindings::{IERC20, IFlashLiquidator, IPriceFeed, LiquidationParams}, simulation_sandbox::{AnvilSandbox, SimResult}},
     constants::{self, TOKEN_DECIMAL_CACHE, TOKEN_SYMBOL_CACHE},
-    morpho::{abi_bindings::IMorphoBlue, morpho_watchlist::MorphoWatchList},
 };
 
-use sled::Db;
-
 #[async_trait::async_trait]
 pub trait Liquidator: Send + Sync {
     async fn run(&self, block_number: u64) -> anyhow::Result<()>;
+
+    /// Cheap check run on every block, independent of the cycle interval
+    async fn on_block(&self, _block_number: u64) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Chainlink aggregators the watched positions are priced with
+    async fn price_feeds(&self) -> anyhow::Result<Vec<Address>> {
+        Ok(vec![])
+    }
+
+    /// Out-of-cycle scan of only the positions priced by `feeds`
+    async fn run_for_feeds(&self, _block_number: u64, _feeds: &[Address]) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -36,7 +46,7 @@ pub trait WatchList<T>: Sync + Send {
 }
 
 pub trait Config: Send + Sync {
-    fn load() -> anyhow::Result<Self>
+    fn load(chain: Arc<ChainProfile>) -> anyhow::Result<Self>
     where
         Self: Sized;
 
@@ -81,21 +91,9 @@ pub enum AdminCmd {
 }
 
 pub struct CoreContracts<M> {
-    pub aave: IAaveV3Pool<M>,
-    pub aave_oracle: AaveOracle<M>,
-    pub ui_pool_data_provider: UiPoolDataProvider<M>,
-    pub morpho: IMorphoBlue<M>,
-    pub comet: IComet<M>,
     pub flash_liq: IFlashLiquidator<M>,
 }
 
-pub struct WatchLists {
-    pub aave_watchlist: Arc<AaveWatchList>,
-    pub morpho_watchlist: Arc<MorphoWatchList>,
-    pub comet_watchlist: Arc<CompoundWatchList>,
-    pub bootstrap_state: Arc<BootstrapState>,
-}
-
 pub async fn execute_liq_tx<M: Middleware + 'static>(
     loan_amt: U256,
     liq_params: LiquidationParams,
@@ -144,6 +142,16 @@ pub async fn simulate_liq_tx<M: Middleware + 'static>(
     
 }
 
+/// Receipt of a mined keeper transaction, an error if it was dropped or reverted
+pub fn ensure_mined(receipt: Option<TransactionReceipt>, what: &str) -> anyhow::Result<TransactionReceipt> {
+    let receipt = receipt.ok_or_else(|| anyhow::anyhow!("{} dropped from mempool", what))?;
+
+    if receipt.status != Some(1.into()) {
+        return Err(anyhow::anyhow!("{} reverted (tx {:?})", what, receipt.transaction_hash));
+    }
+    Ok(receipt)
+}
+
 pub async fn get_token_decimals<M: Middleware + 'static>(
     token: Address,
     provider: Arc<M>,
@@ -174,44 +182,45 @@ pub async fn get_token_symbol<M: Middleware + 'static>(
     Ok(result)
 }
 
+/// Aggregator emitting `AnswerUpdated` behind a Chainlink proxy, `None`
+/// for feeds that are not plain proxies (adapters, fixed prices)
+pub async fn resolve_price_aggregator<M: Middleware + 'static>(
+    feed: Address,
+    provider: Arc<M>,
+) -> Option<Address> {
+    if feed.is_zero() {
+        return None;
+    }
+
+    match IPriceFeed::new(feed, provider).aggregator().call().await {
+        Ok(aggregator) if !aggregator.is_zero() => Some(aggregator),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::debug!("Feed {:?} has no aggregator: {:?}", feed, e);
+            None
+        }
+    }
+}
+
 pub fn fetch_contracts<M: Middleware + 'static>(
+    chain: &ChainProfile,
     client: Arc<M>,
 ) -> anyhow::Result<CoreContracts<M>> {
-    let liq_addr = *constants::FLASH_LIQUIDATOR;
-    let aave_addr = *constants::AAVE_V3_POOL;
-    let comet_addr = *constants::COMET_USDT;
-    let morpho_addr = *constants::MORPHO_BLUE;
-    let oracle_addr = *constants::AAVE_ORACLE;
-    let ui_pool_data_addr = *constants::UIPOOL_DATA_PROVIDER;
+    let liq_addr = chain.flash_liquidator;
 
     let flash_liq = IFlashLiquidator::new(liq_addr, client.clone());
-    let aave = IAaveV3Pool::new(aave_addr, client.clone());
-    let comet = IComet::new(comet_addr, client.clone());
-    let morpho = IMorphoBlue::new(morpho_addr, client.clone());
-    let aave_oracle = AaveOracle::new(oracle_addr, client.clone());
-    let ui_pool_data_provider = UiPoolDataProvider::new(ui_pool_data_addr, client.clone());
 
     Ok(CoreContracts {
-        aave,
-        morpho,
-        comet,
         flash_liq,
-        aave_oracle,
-        ui_pool_data_provider,
-    })
-}
-
-pub fn fetch_watchlists(db: Arc<Db>) -> anyhow::Result<WatchLists> {
-    Ok(WatchLists {
-        aave_watchlist: Arc::new(AaveWatchList::new(db.clone())?),
-        morpho_watchlist: Arc::new(MorphoWatchList::new(db.clone())?),
-        comet_watchlist: Arc::new(CompoundWatchList::new(db.clone())?),
-        bootstrap_state: Arc::new(BootstrapState::new(db)?),
     })
 }
 
-pub async fn create_simulation_sandbox<M: Middleware + 'static>(block_number: u64, f_liq: &IFlashLiquidator<M>) -> anyhow::Result<AnvilSandbox> {
-    let sim_sandbox = AnvilSandbox::new(&*constants::RPC_URL_HTTP, block_number)?;
+pub async fn create_simulation_sandbox<M: Middleware + 'static>(
+    chain: &ChainProfile,
+    block_number: u64,
+    f_liq: &IFlashLiquidator<M>,
+) -> anyhow::Result<AnvilSandbox> {
+    let sim_sandbox = AnvilSandbox::new(&chain.rpc_url_http, chain.chain_id, block_number)?;
     let bytecode = constants::LIQ_BYTECODE.clone();
     let target_address = f_liq.address();
     let keeper_address = constants::WALLET.address();
@@ -225,6 +234,7 @@ pub async fn create_simulation_sandbox<M: Middleware + 'static>(block_number: u6
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct LiquidationRecord {
+    pub chain_id: i64,
     pub timestamp: i64,
     pub block_number: i64,
     pub protocol: String,
@@ -239,6 +249,7 @@ pub struct LiquidationRecord {
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct DistributionRecord {
+    pub chain_id: i64,
     pub tx_hash: String,
     pub asset: String,
     pub asset_symbol: String,
@@ -255,8 +266,8 @@ impl LiquidationRecord {
             r#"
             INSERT OR IGNORE INTO liquidations (
                 tx_hash, protocol, borrower, profit_asset, profit_symbol, 
-                collateral_asset, collateral_symbol, profit_amount, block_number, timestamp
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                collateral_asset, collateral_symbol, profit_amount, block_number, timestamp, chain_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&self.tx_hash)
@@ -269,6 +280,7 @@ impl LiquidationRecord {
         .bind(self.profit_amount)
         .bind(self.block_number)
         .bind(self.timestamp)
+        .bind(self.chain_id)
         .execute(pool)
         .await?;
 
@@ -284,8 +296,8 @@ impl DistributionRecord {
         sqlx::query(
             r#"
             INSERT OR IGNORE INTO distributions (
-                tx_hash, asset, asset_symbol, amount, owner_share, breet_share, timestamp
-            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                tx_hash, asset, asset_symbol, amount, owner_share, breet_share, timestamp, chain_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&self.tx_hash)
@@ -295,6 +307,7 @@ impl DistributionRecord {
         .bind(self.owner_share)
         .bind(self.breet_share)
         .bind(self.timestamp)
+        .bind(self.chain_id)
         .execute(pool)
         .await?;
 
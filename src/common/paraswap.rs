@@ -11,12 +11,14 @@ use crate::common::SwapQueryParams;
 
 /// Price route response from Paraswap
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
  struct PriceRouteResponse {
     pub price_route: serde_json::Value, // Keep it generic for now
 }
 
 /// Transaction response from Paraswap
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
  struct TransactionResponse {
     pub to: String,
     pub from: String,
@@ -74,6 +76,12 @@ impl ParaSwapClient {
         Ok(resp)
     }
 
+    /// Expected output of a swap, without building the transaction
+    pub async fn quote(&self, params: &SwapQueryParams) -> Result<U256> {
+        let price_route_response = self.get_price_route(params).await?;
+        route_amount(&price_route_response.price_route, "destAmount")
+    }
+
     /// Step 2: Call /transactions/:network to build transaction calldata
     async fn build_transaction(
         &self,
@@ -118,26 +126,9 @@ impl ParaSwapClient {
         // Step 1: Get price route
         let price_route_response = self.get_price_route(&params).await?;
         
-        // Step 2: Extract dest_amount from price_route JSON
-        // The structure is: price_route.price_route.destAmount
-        let dest_amount_str = price_route_response.price_route["destAmount"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Failed to extract destAmount from price route"))?;
-
-        let src_amount_str = price_route_response.price_route["srcAmount"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Failed to extract srcAmount from price route"))?;
-        
-        
-        // Parse dest_amount as U256
-        let dest_amount = dest_amount_str
-            .parse::<U256>()
-            .with_context(|| format!("Failed to parse destAmount: {}", dest_amount_str))?;
-        
-        // Parse amount_in from params
-        let src_amount = src_amount_str
-            .parse::<U256>()
-            .with_context(|| format!("Failed to parse amount_in: {}", params.amount))?;
+        // Step 2: Extract the amounts from the priceRoute JSON
+        let dest_amount = route_amount(&price_route_response.price_route, "destAmount")?;
+        let src_amount = route_amount(&price_route_response.price_route, "srcAmount")?;
 
         let slippage_bps = U256::from(params.slippage_bps);
 
@@ -181,3 +172,92 @@ impl ParaSwapClient {
     }
 }
 
+/// Token amounts in a price route are decimal strings. `U256: FromStr`
+/// parses hex, so they go through `from_dec_str`.
+fn route_amount(price_route: &serde_json::Value, key: &str) -> Result<U256> {
+    let raw = price_route[key]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Failed to extract {} from price route", key))?;
+
+    U256::from_dec_str(raw).with_context(|| format!("Failed to parse {}: {}", key, raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `GET /prices` (v6.2) body for 1 WETH → USDC on Polygon
+    const PRICES_BODY: &str = r#"{
+        "priceRoute": {
+            "blockNumber": 63551234,
+            "network": 137,
+            "srcToken": "0x7ceb23fd6bc0add59e62ac25578270cff1b9f619",
+            "srcDecimals": 18,
+            "srcAmount": "1000000000000000000",
+            "destToken": "0x3c499c542cef5e3811e1192ce70d8cc03d5c3359",
+            "destDecimals": 6,
+            "destAmount": "2534816542",
+            "bestRoute": [
+                {
+                    "percent": 100,
+                    "swaps": [
+                        {
+                            "srcToken": "0x7ceb23fd6bc0add59e62ac25578270cff1b9f619",
+                            "srcDecimals": 18,
+                            "destToken": "0x3c499c542cef5e3811e1192ce70d8cc03d5c3359",
+                            "destDecimals": 6,
+                            "swapExchanges": [
+                                {
+                                    "exchange": "UniswapV3",
+                                    "srcAmount": "1000000000000000000",
+                                    "destAmount": "2534816542",
+                                    "percent": 100,
+                                    "data": { "gasUSD": "0.006213" }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ],
+            "gasCostUSD": "0.009744",
+            "gasCost": "123400",
+            "side": "SELL",
+            "version": "6.2",
+            "contractAddress": "0x6a000f20005980200259b80c5102003040001068",
+            "tokenTransferProxy": "0x6a000f20005980200259b80c5102003040001068",
+            "contractMethod": "swapExactAmountIn",
+            "partnerFee": 0,
+            "srcUSD": "2535.4320000000",
+            "destUSD": "2534.6012000000",
+            "partner": "anon",
+            "maxImpactReached": false
+        }
+    }"#;
+
+    #[test]
+    fn price_route_amounts_are_decimal() {
+        let response: PriceRouteResponse = serde_json::from_str(PRICES_BODY).unwrap();
+
+        assert_eq!(
+            route_amount(&response.price_route, "srcAmount").unwrap(),
+            U256::exp10(18)
+        );
+        assert_eq!(
+            route_amount(&response.price_route, "destAmount").unwrap(),
+            U256::from(2_534_816_542u64)
+        );
+        assert_eq!(
+            response.price_route["tokenTransferProxy"].as_str().unwrap().parse::<Address>().unwrap(),
+            "0x6a000f20005980200259b80c5102003040001068".parse::<Address>().unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_or_malformed_amounts_are_errors() {
+        let response: PriceRouteResponse = serde_json::from_str(PRICES_BODY).unwrap();
+        assert!(route_amount(&response.price_route, "amount").is_err());
+
+        let hex = serde_json::json!({ "destAmount": "0x96" });
+        assert!(route_amount(&hex, "destAmount").is_err());
+    }
+}
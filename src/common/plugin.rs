@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+use tokio::sync::{mpsc, watch};
+
+use crate::{
+    bootstrap_engine::{bootstrap_state::BootstrapState, Bootstrap},
+    common::{AdminCmd, Liquidator},
+    position_classifier::PositionClassifier,
+};
+
+/// Shared services handed to every plugin when its engines start
+#[derive(Clone)]
+pub struct EngineContext {
+    pub shutdown_rx: watch::Receiver<bool>,
+    pub classifier: Arc<PositionClassifier>,
+}
+
+/// One running engine instance: its liquidator and the admin channel of
+/// its watchlist updater
+pub struct Engine {
+    pub name: String,
+    pub liquidator: Arc<dyn Liquidator>,
+    pub admin_tx: mpsc::Sender<AdminCmd>,
+}
+
+/// A lending protocol integration. It loads its instances when built,
+/// hands out the bootstraps to run first, then starts one engine per
+/// instance; each engine's watchlist updater handles its admin commands.
+#[async_trait::async_trait]
+pub trait ProtocolPlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn bootstraps(&self, state: Arc<BootstrapState>) -> Vec<Arc<dyn Bootstrap>>;
+
+    async fn start(self: Box<Self>, ctx: EngineContext) -> anyhow::Result<Vec<Engine>>;
+}
+
+/// The protocols `start_liquidation_engines` runs
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn ProtocolPlugin>>,
+}
+
+impl PluginRegistry {
+    pub fn register(&mut self, plugin: impl ProtocolPlugin + 'static) {
+        tracing::info!("🔌 Registered {} plugin", plugin.name());
+        self.plugins.push(Box::new(plugin));
+    }
+
+    pub fn bootstraps(&self, state: Arc<BootstrapState>) -> Vec<Arc<dyn Bootstrap>> {
+        self.plugins
+            .iter()
+            .flat_map(|plugin| plugin.bootstraps(state.clone()))
+            .collect()
+    }
+
+    /// Start every plugin's engines concurrently
+    pub async fn start(self, ctx: EngineContext) -> anyhow::Result<Vec<Engine>> {
+        let started = join_all(self.plugins.into_iter().map(|plugin| plugin.start(ctx.clone()))).await;
+
+        let mut engines = vec![];
+        for result in started {
+            engines.extend(result?);
+        }
+
+        Ok(engines)
+    }
+}
@@ -5,7 +5,6 @@ use ethers::{
         Address, 
         Bytes, 
         TransactionRequest, U256, U64, H256, TransactionReceipt}, utils::{Anvil, AnvilInstance, hex}};
-use crate::constants;
 
 use serde_json::json;
 
@@ -23,11 +22,11 @@ pub struct AnvilSandbox {
 }
 
 impl AnvilSandbox {
-    pub fn new(rpc_url: &str, block_number: u64) -> anyhow::Result<Self> {
+    pub fn new(rpc_url: &str, chain_id: u64, block_number: u64) -> anyhow::Result<Self> {
         let anvil = Anvil::new()
             .fork(rpc_url)
             .fork_block_number(block_number)
-            .chain_id(constants::CHAIN_ID)
+            .chain_id(chain_id)
             .spawn();
 
         let provider = Arc::new(Provider::<Http>::try_from(anvil.endpoint())?);
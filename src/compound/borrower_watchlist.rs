@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+
+use dashmap::DashSet;
+use ethers::{core::rand, types::Address};
+use sled::{Db, Tree};
+
+use crate::common::WatchList;
+
+/// Comet accounts with an open base borrow, candidates for `absorb`
+pub struct CompoundBorrowerWatchList {
+    db: Arc<Tree>,
+    cache: Arc<DashSet<Address>>,
+}
+
+impl CompoundBorrowerWatchList {
+    pub fn new(db: Arc<Db>, namespace: &str) -> anyhow::Result<Self> {
+        let tree = db.open_tree(format!("{}:borrowers", namespace))?;
+        let cache = Arc::new(DashSet::new());
+
+        for item in tree.iter() {
+            let (k, _) = item?;
+            cache.insert(Address::from_slice(&k));
+        }
+
+        Ok(Self {
+            db: Arc::new(tree),
+            cache,
+        })
+    }
+
+    pub fn snapshot(&self) -> Vec<Address> {
+        self.cache.iter().map(|b| *b).collect()
+    }
+
+    pub fn contains(&self, borrower: Address) -> bool {
+        self.cache.contains(&borrower)
+    }
+
+    async fn persist(&self, borrower: Address) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        let tracked = self.cache.contains(&borrower);
+
+        tokio::task::spawn_blocking(move || {
+            if tracked {
+                db.insert(borrower.as_bytes(), &[])?;
+            } else {
+                db.remove(borrower.as_bytes())?;
+            }
+
+            if rand::random::<u8>().is_multiple_of(32) {
+                db.flush()?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WatchList<Address> for CompoundBorrowerWatchList {
+    async fn add(&self, borrower: Address) -> anyhow::Result<()> {
+        if self.cache.insert(borrower) {
+            tracing::debug!("👀 Tracking Comet borrower {:?}", borrower);
+            self.persist(borrower).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, borrower: Address) -> anyhow::Result<()> {
+        if self.cache.remove(&borrower).is_some() {
+            tracing::debug!("🧹 Comet borrower {:?} has no debt", borrower);
+            self.persist(borrower).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ethers::{signers::{LocalWallet, Signer}, types::Address};
+
+use crate::{bootstrap_engine::Protocol, chain_profile::ChainProfile, common::Config, constants};
+
+use super::types::CometSpec;
+
+
+#[derive(Debug, Clone)]
+pub struct CompoundConfig {
+    pub name: String,
+    pub wallet: LocalWallet,
+    pub comet: Address,
+    pub flash_liquidator: Address,
+    pub chain: Arc<ChainProfile>,
+    pub deploy_block: u64,
+}
+
+impl Config for CompoundConfig {
+    fn load(chain: Arc<ChainProfile>) -> anyhow::Result<Self> {
+        let deployment = chain
+            .compound
+            .clone()
+            .ok_or_else(|| anyhow!("Compound is not deployed on {}", chain.name))?;
+
+        Ok(CompoundConfig {
+            name: constants::COMPOUND_DEFAULT_MARKET.to_string(),
+            wallet: chain.wallet(),
+            comet: deployment.comet,
+            flash_liquidator: chain.flash_liquidator,
+            deploy_block: deployment.deploy_block,
+            chain,
+        })
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain.chain_id
+    }
+
+    fn keeper_address(&self) -> Address {
+        self.wallet.address()
+    }
+}
+
+impl CompoundConfig {
+    pub fn for_comet(chain: Arc<ChainProfile>, spec: &CometSpec) -> anyhow::Result<Self> {
+        Ok(CompoundConfig {
+            name: spec.name.clone(),
+            comet: spec.comet,
+            flash_liquidator: spec.flash_liquidator,
+            deploy_block: spec.deploy_block,
+            ..Self::load(chain)?
+        })
+    }
+
+    fn is_default(&self) -> bool {
+        self.name == constants::COMPOUND_DEFAULT_MARKET
+    }
+
+    /// Prefix of the market's sled trees; the default market keeps the
+    /// original `compound:` names
+    pub fn namespace(&self) -> String {
+        if self.is_default() {
+            "compound".to_string()
+        } else {
+            format!("compound:{}", self.name)
+        }
+    }
+
+    /// Bootstrap cursor, the default market keeps its original one
+    pub fn cursor(&self) -> Protocol {
+        if self.is_default() {
+            Protocol::Compound
+        } else {
+            Protocol::CometMarket(self.name.clone())
+        }
+    }
+}
@@ -1,56 +1,77 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use anyhow::{Result, ensure};
 use ethers::{
     providers::Middleware,
     types::{Address, U256},
 };
+use dashmap::DashMap;
 use futures_util::{stream, StreamExt};
+use tokio::sync::Mutex;
 
 use super::{
-    types::BuyCollateralParams,
+    types::{BuyCollateralParams, CometStatus},
     abi_bindings::IComet,
+    borrower_watchlist::CompoundBorrowerWatchList,
     compound_watchlist::CompoundWatchList,
     helpers,
-    //compound_config::CompoundConfig,
+    compound_config::CompoundConfig,
 };
 
 use crate::{common::{
-    self, Liquidator, 
+    Config, Liquidator, 
     SwapQueryParams, abi_bindings::{
         IFlashLiquidator, 
         LiquidationParams
     },
     create_simulation_sandbox, 
+    ensure_mined,
     execute_liq_tx, 
     get_token_decimals, 
     paraswap::ParaSwapClient, 
+    resolve_price_aggregator,
     simulate_liq_tx
-}, constants};
+}};
+
+/// Accounts per `absorb` call
+const ABSORB_BATCH: usize = 10;
 
 pub struct CompoundLiquidator<M: Middleware + 'static> {
     pub comet: IComet<M>,
     pub flash_liquidator: IFlashLiquidator<M>,
     pub client: Arc<M>,
     pub watch_list: Arc<CompoundWatchList>,
-    //pub config: Arc<CompoundConfig>,
+    pub borrowers: Arc<CompoundBorrowerWatchList>,
+    keeper: Address,
+    /// absorber → last seen (numAbsorbs, numAbsorbed, approxSpend)
+    points: DashMap<Address, (u32, u64, u128)>,
+    pub config: Arc<CompoundConfig>,
+    /// Last probed governance status
+    status: Mutex<Option<CometStatus>>,
+    /// asset (collateral or base) → Chainlink aggregator behind its feed
+    asset_feeds: DashMap<Address, Address>,
 }
 
 impl<M: Middleware + 'static> CompoundLiquidator<M> {
     pub fn new(
-        //config: Arc<CompoundConfig>,
+        config: Arc<CompoundConfig>,
         client: Arc<M>,
         watch_list: Arc<CompoundWatchList>,
+        borrowers: Arc<CompoundBorrowerWatchList>,
     ) -> Self {
-        let contracts = common::fetch_contracts(client.clone()).expect("failed to fetch contracts");
-
-        let comet = contracts.comet;
-        let flash_liquidator = contracts.flash_liq;
+        let comet = IComet::new(config.comet, client.clone());
+        let flash_liquidator = IFlashLiquidator::new(config.flash_liquidator, client.clone());
 
         Self {
             comet,
             flash_liquidator,
             client,
             watch_list,
+            borrowers,
+            keeper: config.keeper_address(),
+            points: DashMap::new(),
+            config,
+            status: Mutex::new(None),
+            asset_feeds: DashMap::new(),
         }
     }
 
@@ -119,8 +140,8 @@ impl<M: Middleware + 'static> CompoundLiquidator<M> {
             dest_decimals,
             amount: min_collateral.to_string(), // use min collateral to guarantee swap works
             side: "SELL".into(),
-            chain_id: constants::CHAIN_ID,
-            slippage_bps: 30,
+            chain_id: self.config.chain_id(),
+            slippage_bps: self.config.chain.tuning().compound_slippage_bps,
             user_address: self.flash_liquidator.address().to_string(),
             receiver: self.flash_liquidator.address().to_string(),
         };
@@ -131,9 +152,10 @@ impl<M: Middleware + 'static> CompoundLiquidator<M> {
             .await?;
 
         let min_base_out = route.min_amt_out;
+        let min_profit = base_required * U256::from(self.config.chain.tuning().min_profit_bps) / U256::from(10_000u64);
 
         ensure!(
-            min_base_out >= base_required,
+            min_base_out >= base_required + min_profit,
             "Unprofitable after fee"
         );
 
@@ -149,24 +171,28 @@ impl<M: Middleware + 'static> CompoundLiquidator<M> {
         }))
     }
 
-    /// Generates all profitable arbitrage opportunities
-    async fn generate_arbs(&self) -> Result<Vec<BuyCollateralParams>> {
+    /// Generates all profitable arbitrage opportunities, limited to
+    /// `only` collateral assets when given
+    async fn generate_arbs(&self, only: Option<&HashSet<Address>>) -> Result<Vec<BuyCollateralParams>> {
+
+    let assets: Vec<Address> = self.watch_list
+        .snapshot()
+        .into_iter()
+        .map(|(asset, _)| asset)
+        .filter(|asset| only.is_none_or(|assets| assets.contains(asset)))
+        .collect();
+    if assets.is_empty() {
+        return Ok(vec![]);
+    }
 
-    let snapshot = self.watch_list.snapshot();
+    // Light check: size purchases on the on-chain reserves, not the book
+    let snapshot = helpers::reconcile_reserves(&self.comet, &self.watch_list, assets).await?;
     if snapshot.is_empty() {
         return Ok(vec![]);
     }
 
     // Fetch global state ONCE per block
-    let base_asset = self.comet.base_token().call().await?;
-    let reserves_i256 = self.comet.get_reserves().call().await?;
-    let target_reserves = self.comet.target_reserves().call().await?;
-
-    let base_reserves = if reserves_i256.is_negative() {
-        U256::zero()
-    } else {
-        reserves_i256.into_raw()
-    };
+    let (base_asset, base_reserves, target_reserves) = self.reserve_state().await?;
 
     if base_reserves >= target_reserves {
         return Ok(vec![]);
@@ -196,7 +222,120 @@ impl<M: Middleware + 'static> CompoundLiquidator<M> {
 
     Ok(results)
 }
- 
+
+    /// Base token, base reserves (negative as zero) and target reserves
+    async fn reserve_state(&self) -> Result<(Address, U256, U256)> {
+        let base_asset = self.comet.base_token().call().await?;
+        let reserves_i256 = self.comet.get_reserves().call().await?;
+        let target_reserves = self.comet.target_reserves().call().await?;
+
+        let base_reserves = if reserves_i256.is_negative() {
+            U256::zero()
+        } else {
+            reserves_i256.into_raw()
+        };
+
+        Ok((base_asset, base_reserves, target_reserves))
+    }
+
+    /// Read the current Comet status, cache it and alert when it changes
+    async fn probe_status(&self) -> Result<CometStatus> {
+        let status = helpers::comet_status(&self.comet).await?;
+
+        match self.status.lock().await.replace(status) {
+            Some(previous) if previous != status => {
+                tracing::warn!("🚨 Comet {} status changed: {:?} → {:?}", self.config.name, previous, status);
+            }
+            None if status.buy_paused || status.absorb_paused => {
+                tracing::warn!("🚨 Comet {} is paused: {:?}", self.config.name, status);
+            }
+            _ => {}
+        }
+
+        Ok(status)
+    }
+
+    /// Tracked borrowers `isLiquidatable` reports underwater
+    async fn scan_absorbable(&self) -> Vec<Address> {
+        stream::iter(self.borrowers.snapshot())
+            .map(|account| async move {
+                (account, self.comet.is_liquidatable(account).call().await)
+            })
+            .buffer_unordered(16)
+            .filter_map(|(account, res)| async move {
+                match res {
+                    Ok(liquidatable) => liquidatable.then_some(account),
+                    Err(e) => {
+                        tracing::warn!("isLiquidatable failed for {:?}: {:?}", account, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Absorb underwater accounts in batches. Their collateral joins the
+    /// reserves that the following buy pass sells.
+    ///
+    /// Absorb and buy are two keeper transactions, not one atomic call:
+    /// the deployed flash liquidator has no absorb mode, so another buyer
+    /// can take the absorbed collateral between them.
+    async fn absorb_underwater(&self) {
+        let accounts = self.scan_absorbable().await;
+        if accounts.is_empty() {
+            return;
+        }
+
+        tracing::info!("🧲 {} accounts are absorbable on Comet {}", accounts.len(), self.config.name);
+
+        for batch in accounts.chunks(ABSORB_BATCH) {
+            if let Err(e) = self.absorb(batch).await {
+                tracing::error!("Absorb of {:?} failed: {:?}", batch, e);
+            }
+        }
+
+        self.track_points(&[self.keeper]).await;
+    }
+
+    async fn absorb(&self, accounts: &[Address]) -> Result<()> {
+        let call = self.comet.absorb(self.keeper, accounts.to_vec());
+
+        // Dry run first, another absorber may have been faster
+        call.call().await?;
+
+        let receipt = ensure_mined(call.send().await?.await?, "absorb")?;
+        tracing::info!(
+            "🧲 Absorbed {} Comet accounts (tx {:?})",
+            accounts.len(),
+            receipt.transaction_hash
+        );
+
+        Ok(())
+    }
+
+    /// Log changes of the `liquidatorPoints` earned by our absorbers
+    async fn track_points(&self, absorbers: &[Address]) {
+        for &absorber in absorbers {
+            let points = match self.comet.liquidator_points(absorber).call().await {
+                Ok((num_absorbs, num_absorbed, approx_spend, _)) => (num_absorbs, num_absorbed, approx_spend),
+                Err(e) => {
+                    tracing::debug!("liquidatorPoints unavailable for {:?}: {:?}", absorber, e);
+                    continue;
+                }
+            };
+
+            if self.points.insert(absorber, points) != Some(points) {
+                tracing::info!(
+                    "🏅 Liquidator points of {:?}: {} absorbs, {} accounts absorbed, approx gas spend {}",
+                    absorber,
+                    points.0,
+                    points.1,
+                    points.2
+                );
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -205,11 +344,85 @@ where
     M: Middleware + 'static,
 {
     async fn run(&self, block_number: u64) -> Result<()> {
-        let opportunities = self.generate_arbs().await?;
+        // Probed on every block, only read it again if no probe landed yet
+        let cached = *self.status.lock().await;
+        let status = match cached {
+            Some(status) => status,
+            None => self.probe_status().await?,
+        };
+
+        // Absorb first so this block's buy pass already sees the seized collateral
+        if !status.absorb_paused {
+            self.absorb_underwater().await;
+        }
+
+        if status.buy_paused {
+            return Ok(());
+        }
+
+        let opportunities = self.generate_arbs(None).await?;
+        if !opportunities.is_empty() {
+            self.execute_arbs(opportunities, block_number).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_block(&self, _block_number: u64) -> Result<()> {
+        self.probe_status().await?;
+        Ok(())
+    }
+
+    async fn price_feeds(&self) -> Result<Vec<Address>> {
+        let base_asset = self.comet.base_token().call().await?;
+        let base_feed = self.comet.base_token_price_feed().call().await?;
+        let mut feeds = vec![(base_asset, base_feed)];
+
+        for info in helpers::collateral_assets(&self.comet).await? {
+            feeds.push((info.asset, info.price_feed));
+        }
+
+        self.asset_feeds.clear();
+        for (asset, feed) in feeds {
+            if let Some(aggregator) = resolve_price_aggregator(feed, self.client.clone()).await {
+                self.asset_feeds.insert(asset, aggregator);
+            }
+        }
+
+        Ok(self.asset_feeds.iter().map(|entry| *entry.value()).collect())
+    }
+
+    async fn run_for_feeds(&self, block_number: u64, feeds: &[Address]) -> Result<()> {
+        let assets: HashSet<Address> = self.asset_feeds
+            .iter()
+            .filter(|entry| feeds.contains(entry.value()))
+            .map(|entry| *entry.key())
+            .collect();
+
+        if assets.is_empty() {
+            return Ok(());
+        }
+
+        if self.status.lock().await.is_some_and(|status| status.buy_paused) {
+            return Ok(());
+        }
+
+        // A base price move reprices every collateral
+        let base_asset = self.comet.base_token().call().await?;
+        let only = (!assets.contains(&base_asset)).then_some(&assets);
+
+        let opportunities = self.generate_arbs(only).await?;
         if opportunities.is_empty() {
             return Ok(());
         }
 
+        tracing::info!("Compound {}: Price update, {} buyCollateral opportunities", self.config.name, opportunities.len());
+        self.execute_arbs(opportunities, block_number).await
+    }
+}
+
+impl<M: Middleware + 'static> CompoundLiquidator<M> {
+    async fn execute_arbs(&self, opportunities: Vec<BuyCollateralParams>, block_number: u64) -> Result<()> {
         let jobs = opportunities
             .into_iter()
             .map(|opp| {
@@ -219,7 +432,7 @@ where
             })
             .collect::<Vec<_>>();
 
-        let sim_sandbox = create_simulation_sandbox(block_number, &self.flash_liquidator).await?;
+        let sim_sandbox = create_simulation_sandbox(&self.config.chain, block_number, &self.flash_liquidator).await?;
         let snapshot_id = sim_sandbox.snapshot().await?;
 
         for (debt, liq_params) in jobs {
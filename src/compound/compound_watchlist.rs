@@ -23,8 +23,8 @@ pub struct CompoundWatchList {
 
 impl CompoundWatchList {
     /// Initialize watchlist from sled DB
-    pub fn new(db: Arc<Db>) -> anyhow::Result<Self> {
-        let tree = db.open_tree("compound:reserves")?;
+    pub fn new(db: Arc<Db>, namespace: &str) -> anyhow::Result<Self> {
+        let tree = db.open_tree(format!("{}:reserves", namespace))?;
         let cache = Arc::new(DashMap::new());
 
         // Load persisted state into memory
@@ -55,6 +55,22 @@ impl CompoundWatchList {
         self.cache.get(&asset).map(|v| *v)
     }
 
+    /// Overwrite an asset's amount with the on-chain reserve, returning
+    /// the previous book value
+    pub async fn set(&self, asset: Address, amount: U256) -> anyhow::Result<U256> {
+        let previous = if amount.is_zero() {
+            self.cache.remove(&asset).map(|(_, v)| v)
+        } else {
+            self.cache.insert(asset, amount)
+        }
+        .unwrap_or_default();
+
+        if previous != amount {
+            self.persist(asset).await?;
+        }
+        Ok(previous)
+    }
+
     /// Internal persist helper
     async fn persist(&self, asset: Address) -> anyhow::Result<()> {
         let db = self.db.clone();
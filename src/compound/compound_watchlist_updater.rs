@@ -1,18 +1,21 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use anyhow::Result;
-use ethers::{providers::Middleware};
+use ethers::{providers::Middleware, types::Address};
 use futures_util::StreamExt;
 use tokio::sync::{mpsc, watch};
 
 use super::{
     abi_bindings::{IComet, ICometEvents},
+    borrower_watchlist::CompoundBorrowerWatchList,
     compound_watchlist::CompoundWatchList,
+    helpers,
 };
 
 use crate::common::{WatchList, AdminCmd};
 
 pub struct CompoundWatchListUpdater<M: Middleware + 'static> {
     watch_list: Arc<CompoundWatchList>,
+    borrowers: Arc<CompoundBorrowerWatchList>,
     comet: Arc<IComet<M>>,
     shutdown: watch::Receiver<bool>,
     cmd_rx: mpsc::Receiver<AdminCmd>,
@@ -22,12 +25,14 @@ impl<M: Middleware + Send + Sync + 'static> CompoundWatchListUpdater<M> {
 
     pub fn new(
         watch_list: Arc<CompoundWatchList>,
+        borrowers: Arc<CompoundBorrowerWatchList>,
         comet: Arc<IComet<M>>,
         shutdown: watch::Receiver<bool>,
         cmd_rx: mpsc::Receiver<AdminCmd>,
     ) -> Self {
         Self {
             watch_list,
+            borrowers,
             comet,
             shutdown,
             cmd_rx,
@@ -80,8 +85,17 @@ impl<M: Middleware + Send + Sync + 'static> CompoundWatchListUpdater<M> {
                             );
                         }
                         Some(AdminCmd::Prune) => {
-                            // No pruning needed for Compound
-                            tracing::info!("🧹 Prune ignored (not applicable for Compound)");
+                            tracing::info!("🧹 Reconciling Comet reserves...");
+                            let assets = helpers::collateral_assets(&self.comet).await?;
+                            helpers::reconcile_reserves(
+                                &self.comet,
+                                &self.watch_list,
+                                assets.into_iter().map(|info| info.asset),
+                            ).await?;
+
+                            tracing::info!("🧹 Pruning Comet borrowers...");
+                            let accounts = self.borrowers.snapshot().into_iter().collect();
+                            helpers::refresh_borrowers(&self.comet, &self.borrowers, accounts).await?;
                         }
                         None => {
                             tracing::warn!("Admin channel closed");
@@ -96,6 +110,10 @@ impl<M: Middleware + Send + Sync + 'static> CompoundWatchListUpdater<M> {
         Ok(())
     }
 
+    async fn refresh_borrower(&self, account: Address) -> Result<()> {
+        helpers::refresh_borrowers(&self.comet, &self.borrowers, HashSet::from([account])).await
+    }
+
     async fn handle_event(&self, event: ICometEvents) -> Result<()> {
 
         match event {
@@ -128,6 +146,17 @@ impl<M: Middleware + Send + Sync + 'static> CompoundWatchListUpdater<M> {
                 );
             }
 
+            // 👀 Base borrow opened, repaid or absorbed
+            ICometEvents::WithdrawFilter(f) => {
+                self.refresh_borrower(f.src).await?;
+            }
+            ICometEvents::SupplyFilter(f) if self.borrowers.contains(f.dst) => {
+                self.refresh_borrower(f.dst).await?;
+            }
+            ICometEvents::AbsorbDebtFilter(f) => {
+                self.refresh_borrower(f.borrower).await?;
+            }
+
             _ => {}
         }
 
@@ -1,5 +1,15 @@
-use super::abi_bindings::IComet;
+use std::collections::HashSet;
+
+use super::{
+    abi_bindings::{AssetInfo, IComet},
+    borrower_watchlist::CompoundBorrowerWatchList,
+    compound_watchlist::CompoundWatchList,
+    types::CometStatus,
+};
 use ethers::{types::{Address, U256, U512}, providers::Middleware};
+use futures_util::{stream, StreamExt};
+
+use crate::common::WatchList;
 
 use anyhow::{anyhow,ensure};
 
@@ -97,3 +107,112 @@ pub async fn base_amount_for_collateral<M: Middleware + 'static>(
     Ok(base_required.min(max_base_cap))
 }
 
+/// Pause flags and trading parameters of the Comet
+pub async fn comet_status<M: Middleware + 'static>(
+    comet: &IComet<M>,
+) -> anyhow::Result<CometStatus> {
+    let buy_paused = comet.is_buy_paused();
+    let absorb_paused = comet.is_absorb_paused();
+    let base_borrow_min = comet.base_borrow_min();
+    let store_front_price_factor = comet.store_front_price_factor();
+
+    let (buy_paused, absorb_paused, base_borrow_min, store_front_price_factor) = tokio::try_join!(
+        buy_paused.call(),
+        absorb_paused.call(),
+        base_borrow_min.call(),
+        store_front_price_factor.call(),
+    )?;
+
+    Ok(CometStatus {
+        buy_paused,
+        absorb_paused,
+        base_borrow_min,
+        store_front_price_factor,
+    })
+}
+
+/// Collateral assets listed on the Comet, from `numAssets` / `getAssetInfo`
+pub async fn collateral_assets<M: Middleware + 'static>(
+    comet: &IComet<M>,
+) -> anyhow::Result<Vec<AssetInfo>> {
+    let num_assets = comet.num_assets().call().await?;
+    let mut assets = Vec::with_capacity(num_assets as usize);
+
+    for i in 0..num_assets {
+        assets.push(comet.get_asset_info(i).call().await?);
+    }
+
+    Ok(assets)
+}
+
+/// Reset the reserve book of `assets` to `getCollateralReserves` and
+/// return the on-chain amounts. Assets whose read fails keep their
+/// book value and are left out.
+pub async fn reconcile_reserves<M: Middleware + 'static>(
+    comet: &IComet<M>,
+    watch_list: &CompoundWatchList,
+    assets: impl IntoIterator<Item = Address>,
+) -> anyhow::Result<Vec<(Address, U256)>> {
+    let reads: Vec<(Address, anyhow::Result<U256>)> = stream::iter(assets)
+        .map(|asset| async move {
+            let reserves = comet.get_collateral_reserves(asset).call().await.map_err(anyhow::Error::from);
+            (asset, reserves)
+        })
+        .buffer_unordered(8)
+        .collect()
+        .await;
+
+    let mut reserves = Vec::with_capacity(reads.len());
+    for (asset, read) in reads {
+        let on_chain = match read {
+            Ok(amount) => amount,
+            Err(e) => {
+                tracing::warn!("Failed to read Comet reserves of {:?}: {:?}", asset, e);
+                continue;
+            }
+        };
+
+        let booked = watch_list.set(asset, on_chain).await?;
+        if booked != on_chain {
+            tracing::warn!(
+                "📐 Comet reserve drift on {:?}: book {} vs on-chain {}",
+                asset,
+                booked,
+                on_chain
+            );
+        }
+
+        if !on_chain.is_zero() {
+            reserves.push((asset, on_chain));
+        }
+    }
+
+    Ok(reserves)
+}
+
+/// Track accounts that hold a base borrow, drop the ones that repaid or
+/// were absorbed
+pub async fn refresh_borrowers<M: Middleware + 'static>(
+    comet: &IComet<M>,
+    borrowers: &CompoundBorrowerWatchList,
+    accounts: HashSet<Address>,
+) -> anyhow::Result<()> {
+    let balances: Vec<(Address, anyhow::Result<U256>)> = stream::iter(accounts)
+        .map(|account| async move {
+            let balance = comet.borrow_balance_of(account).call().await.map_err(anyhow::Error::from);
+            (account, balance)
+        })
+        .buffer_unordered(16)
+        .collect()
+        .await;
+
+    for (account, balance) in balances {
+        match balance {
+            Ok(balance) if balance.is_zero() => borrowers.remove(account).await?,
+            Ok(_) => borrowers.add(account).await?,
+            Err(e) => tracing::warn!("Failed to read Comet borrow of {:?}: {:?}", account, e),
+        }
+    }
+
+    Ok(())
+}
@@ -1,45 +1,82 @@
 use ethers::providers::Middleware;
+use sled::Db;
 use std::sync::Arc;
 use tokio::sync::{mpsc, watch};
 
 use crate::{
-    common::{task_manager::spawn_named_and_register, AdminCmd, Liquidator},
+    common::{task_manager::spawn_named_and_register, AdminCmd, Config, Liquidator},
     compound::{
-        abi_bindings::IComet, compound_liquidator::CompoundLiquidator,
+        abi_bindings::IComet, borrower_watchlist::CompoundBorrowerWatchList,
+        compound_config::CompoundConfig,
+        compound_liquidator::CompoundLiquidator,
         compound_watchlist::CompoundWatchList,
         compound_watchlist_updater::CompoundWatchListUpdater,
     },
+    chain_profile::ChainProfile,
 };
 
 pub mod abi_bindings;
+pub mod borrower_watchlist;
+pub mod compound_config;
 pub mod compound_liquidator;
 pub mod compound_watchlist;
 pub mod compound_watchlist_updater;
 pub mod helpers;
+pub mod plugin;
 pub mod types;
 
+/// Per-market state of one Compound engine instance
+pub struct CompoundInstance {
+    pub config: Arc<CompoundConfig>,
+    pub watch_list: Arc<CompoundWatchList>,
+    pub borrowers: Arc<CompoundBorrowerWatchList>,
+}
+
+/// Load the chain's default Comet and its extra Comets
+pub fn load_instances(chain: Arc<ChainProfile>, db: Arc<Db>) -> anyhow::Result<Vec<CompoundInstance>> {
+    let mut configs = vec![CompoundConfig::load(chain.clone())?];
+    for spec in chain.compound.iter().flat_map(|compound| compound.extra_comets.iter()) {
+        configs.push(CompoundConfig::for_comet(chain.clone(), spec)?);
+    }
+
+    configs
+        .into_iter()
+        .map(|config| {
+            let namespace = config.namespace();
+            Ok(CompoundInstance {
+                watch_list: Arc::new(CompoundWatchList::new(db.clone(), &namespace)?),
+                borrowers: Arc::new(CompoundBorrowerWatchList::new(db.clone(), &namespace)?),
+                config: Arc::new(config),
+            })
+        })
+        .collect()
+}
+
 pub async fn start_engine<M: Middleware + 'static>(
     client: Arc<M>,
-    watch_list: Arc<CompoundWatchList>,
-    comet: IComet<M>,
     shutdown_rx: watch::Receiver<bool>,
     prune_rx: mpsc::Receiver<AdminCmd>,
+    instance: CompoundInstance,
 ) -> anyhow::Result<Arc<dyn Liquidator>> {
+    let CompoundInstance { config, watch_list, borrowers } = instance;
+
     let comet_liq = Arc::new(
-        CompoundLiquidator::new(client.clone(), watch_list.clone())
+        CompoundLiquidator::new(config.clone(), client.clone(), watch_list.clone(), borrowers.clone())
     );
+    let comet = IComet::new(config.comet, client);
 
     spawn_named_and_register("compound_watchlist_updater", async move {
         
         let updater = CompoundWatchListUpdater::new(
             watch_list.clone(), 
+            borrowers,
             Arc::new(comet), 
             shutdown_rx, 
             prune_rx
         );
 
         if let Err(e) = updater.start().await {
-            tracing::error!("Compound WatchListUpdater error: {:?}", e);
+            tracing::error!("Compound WatchListUpdater error for {}: {:?}", config.name, e);
         }
     }).await;
 
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use ethers::providers::Middleware;
+use sled::Db;
+use tokio::sync::mpsc;
+
+use crate::{
+    bootstrap_engine::{bootstrap_state::BootstrapState, compound_bootstrap::CompoundBootstrap, Bootstrap},
+    chain_profile::ChainProfile,
+    common::plugin::{Engine, EngineContext, ProtocolPlugin},
+};
+
+use super::{abi_bindings::IComet, CompoundInstance};
+
+/// Compound III: one engine per Comet market
+pub struct CompoundPlugin<M> {
+    client: Arc<M>,
+    instances: Vec<CompoundInstance>,
+}
+
+impl<M: Middleware + 'static> CompoundPlugin<M> {
+    pub fn load(chain: Arc<ChainProfile>, client: Arc<M>, db: Arc<Db>) -> anyhow::Result<Self> {
+        let instances = super::load_instances(chain, db)?;
+        Ok(Self { client, instances })
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> ProtocolPlugin for CompoundPlugin<M> {
+    fn name(&self) -> &'static str {
+        "compound"
+    }
+
+    fn bootstraps(&self, state: Arc<BootstrapState>) -> Vec<Arc<dyn Bootstrap>> {
+        self.instances
+            .iter()
+            .map(|instance| -> Arc<dyn Bootstrap> {
+                Arc::new(CompoundBootstrap::new(
+                    IComet::new(instance.config.comet, self.client.clone()),
+                    instance.watch_list.clone(),
+                    instance.borrowers.clone(),
+                    state.clone(),
+                    self.client.clone(),
+                    instance.config.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    async fn start(self: Box<Self>, ctx: EngineContext) -> anyhow::Result<Vec<Engine>> {
+        let mut engines = Vec::with_capacity(self.instances.len());
+
+        for instance in self.instances {
+            let name = instance.config.namespace();
+            let (admin_tx, admin_rx) = mpsc::channel(64);
+            let liquidator = super::start_engine(
+                self.client.clone(),
+                ctx.shutdown_rx.clone(),
+                admin_rx,
+                instance,
+            ).await?;
+
+            engines.push(Engine { name, liquidator, admin_tx });
+        }
+
+        Ok(engines)
+    }
+}
@@ -1,4 +1,7 @@
 use ethers::types::{Address, Bytes, U256};
+use serde::Deserialize;
+
+use crate::common::abi_bindings::LiquidationParams;
 
 
 pub struct BuyCollateralParams{
@@ -9,6 +12,48 @@ pub struct BuyCollateralParams{
     pub swap_target: Address,
     pub swap_proxy: Address,
     pub swap_data: Bytes,
-    pub min_base_out: U256
+    pub min_base_out: U256,
+}
+
+/// Comet governance state that gates buying and absorbing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CometStatus {
+    pub buy_paused: bool,
+    pub absorb_paused: bool,
+    pub base_borrow_min: U256,
+    pub store_front_price_factor: U256,
+}
+
+/// A Comet market to run an engine instance against
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CometSpec {
+    pub name: String,
+    pub comet: Address,
+    pub flash_liquidator: Address,
+    pub deploy_block: u64,
+}
 
-}
\ No newline at end of file
+impl From<BuyCollateralParams> for LiquidationParams {
+    fn from(value: BuyCollateralParams) -> Self {
+        Self { 
+            mode: 2, 
+            borrower: Address::zero(), 
+            aave_debt_asset: Address::zero(), 
+            aave_collateral: Address::zero(), 
+            aave_debt_to_cover: U256::zero(), 
+            morpho_market_id: [0u8; 32], 
+            morpho_repaid_shares: U256::zero(), 
+            morpho_seized_assets: U256::zero(),
+            compound_collateral: value.collateral_asset,
+            compound_debt_asset: value.base_asset,
+            compound_debt_to_cover: value.base_amount,
+            compound_min_collateral: value.min_collateral, 
+            swap_target: value.swap_target, 
+            swap_allowance_target: value.swap_proxy, 
+            swap_data: value.swap_data, 
+            flash_asset: value.base_asset, 
+            min_amt_out: value.min_base_out
+        }
+    }
+}
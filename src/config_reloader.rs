@@ -0,0 +1,90 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::watch,
+};
+
+use crate::{
+    chain_profile::{load_profiles, ChainProfile},
+    constants,
+    settings::Settings,
+};
+
+/// Re-reads the settings on SIGHUP or when the config file changes and
+/// pushes the reloadable part to the running chains
+pub struct ConfigReloader {
+    chains: Vec<Arc<ChainProfile>>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl ConfigReloader {
+    pub fn new(chains: Vec<Arc<ChainProfile>>, shutdown: watch::Receiver<bool>) -> Self {
+        Self { chains, shutdown }
+    }
+
+    pub async fn start(&mut self) -> anyhow::Result<()> {
+        let mut hangup = signal(SignalKind::hangup())?;
+        let mut ticker = tokio::time::interval(Duration::from_secs(constants::CONFIG_POLL_SECS));
+        let mut modified = Settings::modified();
+
+        tracing::info!("📡 ConfigReloader watching {:?}", Settings::path());
+        loop {
+            tokio::select! {
+                _ = self.shutdown.changed() => {
+                    tracing::info!("🛑 ConfigReloader shutting down");
+                    break;
+                }
+
+                _ = hangup.recv() => {
+                    tracing::info!("🔔 SIGHUP received, reloading config");
+                    modified = Settings::modified();
+                    self.reload();
+                }
+
+                _ = ticker.tick() => {
+                    let current = Settings::modified();
+                    if current != modified {
+                        tracing::info!("🔔 Config file changed, reloading");
+                        modified = current;
+                        self.reload();
+                    }
+                }
+            }
+        }
+
+        tracing::info!("✅ ConfigReloader stopped cleanly");
+        Ok(())
+    }
+
+    /// All or nothing: an invalid file leaves every chain untouched
+    fn reload(&self) {
+        let fresh = match Settings::load().and_then(|settings| load_profiles(&settings)) {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                tracing::error!("❌ Config reload rejected, keeping current settings: {:?}", e);
+                return;
+            }
+        };
+
+        for chain in &self.chains {
+            let Some(profile) = fresh.iter().find(|p| p.name == chain.name) else {
+                tracing::warn!("⚠️ {} is no longer configured, restart to stop it", chain.name);
+                continue;
+            };
+
+            let next = profile.live.borrow().clone();
+            let changes = chain.live.borrow().diff(&next);
+            if changes.is_empty() {
+                continue;
+            }
+
+            tracing::info!("🔄 {} settings reloaded: {}", chain.name, changes.join(", "));
+            chain.live.send_replace(next);
+        }
+
+        for profile in fresh.iter().filter(|p| !self.chains.iter().any(|c| c.name == p.name)) {
+            tracing::warn!("⚠️ New chain {} needs a restart to start", profile.name);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use ethers::{
      signers::{LocalWallet, Signer}, 
-     types::{Address, Bytes, H256, U256}, 
-     utils::parse_ether
+     types::{Address, Bytes, U256}, 
 };
+use anyhow::{bail, ensure, Context, Result};
 use once_cell::sync::Lazy;
 use tokio::{
     sync::Mutex,
@@ -14,19 +14,20 @@ use secrecy::{SecretString, ExposeSecret};
 use std::env;
 use dashmap::DashMap;
 
+use crate::{aave::types::AavePoolSpec, compound::types::CometSpec};
+
 
 // Shared
+pub const DEFAULT_CONFIG_PATH: &str = "./liq-ranger.toml";
+pub const DEFAULT_CHAIN: &str = "polygon";
 pub const CHAIN_ID: u64 = 137;
-pub const SLED_PATH: &str = "./data/sled_db";
-pub const LIQ_EXECUTOR_INTERVAL: u64 = 10;
-pub const PRUNE_INTERVAL: u64 = 50;
-
-pub static DATABASE_URL: Lazy<String> = Lazy::new(|| {
-    env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite://./data/history.db".to_string())
-});
+pub const PRICE_FEED_REFRESH_SECS: u64 = 600;
+pub const HOPELESS_RECHECK_SECS: u64 = 1_800; // unprofitable / bad-debt positions are re-analyzed after this
+pub const BAD_DEBT_REPORT_SECS: u64 = 3_600;
+pub const CONFIG_POLL_SECS: u64 = 5; // config file mtime checks for hot reload
 
 pub const AAVE_DEPLOY_BLOCK: u64 = 75_000_000;
-//pub const COMPOUND_DEPLOY_BLOCK: u64 = 42_000_000;
+pub const COMPOUND_DEPLOY_BLOCK: u64 = 42_000_000;
 pub const MORPHO_DEPLOY_BLOCK: u64 = 68_000_000;
 
 pub static FLASH_LIQUIDATOR: Lazy<Address> = Lazy::new(|| {
@@ -54,18 +55,22 @@ pub static BREET: Lazy<Address> = Lazy::new(||
 
 );
 
-pub static GAS_THRESHOLD: Lazy<U256> = Lazy::new(|| parse_ether(10u64).expect("Failed"));
-pub static REFUEL_AMT: Lazy<U256> = Lazy::new(|| parse_ether(100u64).expect("Failed"));
+// Whole native tokens
+pub const GAS_THRESHOLD: u64 = 10;
+pub const REFUEL_AMOUNT: u64 = 100;
 
 pub static PROFIT_DIST_ASSETS: Lazy<Vec<Address>> = Lazy::new(|| {
-    let mut profit_assets: Vec<Address> = AAVE_RESERVES.iter().cloned().collect();
-    if !profit_assets.contains(&WPOL) {
-        profit_assets.push(*WPOL);
-    }
-
-    profit_assets
-
-
+    [
+        "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359", // USDC
+        "0xc2132D05D31c914a87C6611C10748AEb04B58e8F", // USDT
+        "0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619", // WETH
+        "0x8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063", // DAI
+        "0x1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6", // WBTC
+        "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270", // WPOL
+    ]
+    .into_iter()
+    .map(|s| s.parse::<Address>().expect("invalid profit asset address"))
+    .collect()
 });
 
 pub static TOKEN_DECIMAL_CACHE: Lazy<DashMap<Address, u8>> = Lazy::new(|| DashMap::new() );
@@ -119,28 +124,12 @@ pub static  LIQUIDATION_CURSOR: Lazy<U256> = Lazy::new(||{
     liquidation_cursor()
 });
 
-pub static MORPHO_MARKETS: Lazy<HashSet<H256>> = Lazy::new(|| {
-    [
-        "0x1cfe584af3db05c7f39d60e458a87a8b2f6b5d8c6125631984ec489f1d13553b",
-        "0x2476bb905e3d94acd7b402b3d70d411eeb6ace82afd3007da69a0d5904dfc998",
-        "0x1947267c49c3629c5ed59c88c411e8cf28c4d2afdb5da046dc8e3846a4761794",
-        "0x7506b33817b57f686e37b87b5d4c5c93fdef4cffd21bbf9291f18b2f29ab0550",
-        "0x267f344f5af0d85e95f253a2f250985a9fb9fca34a3342299e20c83b6906fc80",
-        "0xa5b7ae7654d5041c28cb621ee93397394c7aee6c6e16c7e0fd030128d87ee1a3",
-        "0x41e537c46cc0e2f82aa69107cd72573f585602d8c33c9b440e08eaba5e8fded1",
-        "0x96e62bd75493006b81dae51d5db3c5af4b3ced65133dab60e70df9dc8e38bf2c",
-        "0xb8ae474af3b91c8143303723618b31683b52e9c86566aa54c06f0bc27906bcae",
-        "0x28d8d92f5392c1b26e82dcbec25949ed028ea5b99d5a929ce485f0fd88e47fcc",
-        "0x01550b8779f4ca978fc16591537f3852c02c3491f597db93d9bb299dcbf5ddbe",
-        "0xa932e0d8a9bf52d45b8feac2584c7738c12cf63ba6dff0e8f199e289fb5ca9bb"
-    ]
-    .into_iter()
-    .map(|s| H256::from_str(s).expect("invalid Morpho market id"))
-    .collect()
-});
+pub const PRE_LIQUIDATION_REPAY_BUFFER_BPS: u64 = 10; // headroom for interest accrued before preLiquidate is mined
 
     // Aave
-pub const HF_LIQUIDATION_THRESHOLD_BPS: u128 = 9_500; // 0.95
+pub const AAVE_DEFAULT_POOL: &str = "aave";
+pub const AAVE_INVENTORY_UNWIND_INTERVAL: u64 = 300; // blocks between aToken inventory unwinds
+pub const HF_CONFIRM_THRESHOLD_BPS: u128 = 10_100; // 1.01, locally computed HF below this is checked on-chain
 pub static UIPOOL_DATA_PROVIDER: Lazy<Address> = Lazy::new(||{
     Address::from_str("0xFa1A7c4a8A63C9CAb150529c26f182cBB5500944").expect("Failed")
 });
@@ -154,42 +143,13 @@ pub static  POOL_ADDRESS_PROVIDER: Lazy<Address> = Lazy::new(||{
     Address::from_str("0xa97684ead0e402dC232d5A977953DF7ECBaB3CDb").expect("Failed")
 });
 
-pub static AAVE_RESERVES: Lazy<HashSet<Address>> = Lazy::new(|| {
-    [
-        "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359", // USDC
-        "0xc2132D05D31c914a87C6611C10748AEb04B58e8F", // USDT
-        "0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619", // WETH
-        "0x8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063",  // DAI
-        "0x1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6"  // WBTC
-    ]
-    .into_iter()
-    .map(|s| s.parse::<Address>().expect("invalid reserve address"))
-    .collect()
-});
-
-pub static ATOKENS_ADDR: Lazy<DashMap<Address, Address>> = Lazy::new(|| {
-    DashMap::new()
-
-});
-
 //compound
 
 pub static COMET_USDT: Lazy<Address> = Lazy::new(||
     Address::from_str("0xaeB318360f27748Acb200CE616E389A6C9409a07").expect("Failed")
 );
 
-pub static COMPOUND_RESERVES: Lazy<HashSet<Address>> = Lazy::new(|| {
-    [
-        "0xfa68FB4628DFF1028CFEc22b4162FCcd0d45efb6",
-        "0x3A58a54C066FdC0f2D55FC9C89F0415C92eBf3C4",
-        "0x1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6",
-        "0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619",
-        "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270"
-    ]
-    .into_iter()
-    .map(|s| s.parse::<Address>().expect("invalid reserve address"))
-    .collect()
-});
+pub const COMPOUND_DEFAULT_MARKET: &str = "usdt";
 
 //helpers
 
@@ -223,6 +183,99 @@ fn load_private_key() -> String {
     }
 }
 
+pub fn load_address_list(var: &str) -> Result<HashSet<Address>> {
+    parse_address_list(var, &env::var(var).unwrap_or_default())
+}
+
+fn parse_address_list(var: &str, raw: &str) -> Result<HashSet<Address>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<Address>().with_context(|| format!("invalid address in {}: {}", var, s)))
+        .collect()
+}
+
+pub fn load_optional_address(var: &str) -> Result<Option<Address>> {
+    env::var(var)
+        .ok()
+        .map(|s| s.trim().parse().with_context(|| format!("invalid address in {}: {}", var, s)))
+        .transpose()
+}
+
+pub fn load_u64(var: &str, default: u64) -> Result<u64> {
+    env::var(var)
+        .ok()
+        .map(|v| v.trim().parse().with_context(|| format!("invalid integer in {}: {}", var, v)))
+        .unwrap_or(Ok(default))
+}
+
+/// Extra Aave v3 compatible pools, `;`-separated entries of
+/// `name:addresses_provider:ui_pool_data_provider:flash_liquidator:deploy_block`,
+/// optionally followed by `:allowlist:denylist` of comma-separated reserves
+pub fn load_aave_pools(var: &str) -> Result<Vec<AavePoolSpec>> {
+    let address = |s: &str| s.parse::<Address>().with_context(|| format!("invalid address in {}: {}", var, s));
+
+    env::var(var)
+        .unwrap_or_default()
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            let (name, provider, ui_pool_data, flash_liquidator, deploy_block, allowlist, denylist) = match parts[..] {
+                [name, provider, ui_pool_data, flash_liquidator, deploy_block] => {
+                    (name, provider, ui_pool_data, flash_liquidator, deploy_block, "", "")
+                }
+                [name, provider, ui_pool_data, flash_liquidator, deploy_block, allowlist, denylist] => {
+                    (name, provider, ui_pool_data, flash_liquidator, deploy_block, allowlist, denylist)
+                }
+                _ => bail!("invalid pool entry in {}: {}", var, entry),
+            };
+            ensure!(name != AAVE_DEFAULT_POOL, "{}: {} is reserved for the default pool", var, AAVE_DEFAULT_POOL);
+
+            Ok(AavePoolSpec {
+                name: name.to_string(),
+                pool_address_provider: address(provider)?,
+                ui_pool_data: address(ui_pool_data)?,
+                flash_liquidator: address(flash_liquidator)?,
+                deploy_block: deploy_block
+                    .parse()
+                    .with_context(|| format!("invalid deploy block in {}: {}", var, entry))?,
+                reserve_allowlist: parse_address_list(var, allowlist)?,
+                reserve_denylist: parse_address_list(var, denylist)?,
+            })
+        })
+        .collect()
+}
+
+/// Extra Comet markets, `;`-separated `name:comet:flash_liquidator:deploy_block`
+pub fn load_comets(var: &str) -> Result<Vec<CometSpec>> {
+    let address = |s: &str| s.parse::<Address>().with_context(|| format!("invalid address in {}: {}", var, s));
+
+    env::var(var)
+        .unwrap_or_default()
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            let [name, comet, flash_liquidator, deploy_block] = parts[..] else {
+                bail!("invalid Comet entry in {}: {}", var, entry);
+            };
+            ensure!(name != COMPOUND_DEFAULT_MARKET, "{}: {} is reserved for the default Comet", var, COMPOUND_DEFAULT_MARKET);
+
+            Ok(CometSpec {
+                name: name.to_string(),
+                comet: address(comet)?,
+                flash_liquidator: address(flash_liquidator)?,
+                deploy_block: deploy_block
+                    .parse()
+                    .with_context(|| format!("invalid deploy block in {}: {}", var, entry))?,
+            })
+        })
+        .collect()
+}
+
  fn load_rpc_url() -> String {
     match env::var("RPC_URL") {
         Ok(key) => key,
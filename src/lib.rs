@@ -1,12 +1,17 @@
 mod aave;
 mod block_watcher;
 mod bootstrap_engine;
+mod chain_profile;
 mod common;
+mod config_reloader;
 mod compound;
 mod constants;
 mod liquidation_executor;
+mod price_watcher;
+mod position_classifier;
 mod morpho;
 mod profit_distributor;
+mod settings;
 mod watchlist_pruner;
 mod liq_data_extractor;
 mod db;
@@ -15,8 +20,9 @@ use std::{fs, path::Path, sync::Arc};
 
 use ethers::{
     middleware::{NonceManagerMiddleware, SignerMiddleware},
-    providers::{Provider, Ws, Http},
+    providers::{Middleware, Provider, Ws, Http},
     signers::Signer,
+    types::Address,
 };
 
 use tokio::sync::{broadcast, mpsc, watch};
@@ -24,132 +30,150 @@ use tokio::sync::{broadcast, mpsc, watch};
 use url::Url;
 
 use crate::{
+    aave::plugin::AavePlugin,
+    bootstrap_engine::bootstrap_state::BootstrapState,
+    chain_profile::{load_profiles, ChainProfile},
     common::{
-        fetch_contracts, fetch_watchlists,
+        fetch_contracts,
+        plugin::{EngineContext, PluginRegistry},
         task_manager::{shutdown_all_tasks,spawn_named_and_register},
-        AdminCmd,
-        Liquidator,
     },
+    compound::plugin::CompoundPlugin,
+    morpho::plugin::MorphoPlugin,
     profit_distributor::ProfitDistributor,
     watchlist_pruner::WatchListPruner,
     liq_data_extractor::LiqDataExtractor,
-};
-use bootstrap_engine::{
-    Bootstrap,
-    aave_bootstrap::AaveBootstrap, 
-    morpho_bootstrap::MorphoBootstrap, 
-    compound_bootstrap::CompoundBootstrap,
+    position_classifier::PositionClassifier,
+    settings::Settings,
 };
 
 pub async fn start_liquidation_engines() -> anyhow::Result<()> {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let settings = Settings::load()?;
+    let sqlite_pool = db::connect(&settings.database_url).await?;
+    let chains = load_profiles(&settings)?;
+
+    let started = futures_util::future::join_all(chains.into_iter().map(|chain| {
+        let sqlite_pool = sqlite_pool.clone();
+        let shutdown_rx = shutdown_rx.clone();
+        async move {
+            let result = start_chain(chain.clone(), sqlite_pool, shutdown_rx).await;
+            (chain, result)
+        }
+    })).await;
+
+    let mut running = Vec::new();
+    for (chain, result) in started {
+        match result {
+            Ok(()) => {
+                tracing::info!("⛓️ Engines running on {} (chain {})", chain.name, chain.chain_id);
+                running.push(chain);
+            }
+            // The other chains keep running when an added chain fails to start
+            Err(e) if !chain.is_default() => tracing::error!("❌ Failed to start {}: {:?}", chain.name, e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut config_reloader = config_reloader::ConfigReloader::new(running, shutdown_rx.clone());
+    spawn_named_and_register("config_reloader", async move {
+        if let Err(e) = config_reloader.start().await {
+            tracing::error!("❌ Config reloader failed: {:?}", e);
+        }
+    }).await;
+
+    tracing::info!("🚀 Liquidation system started");
+    tokio::signal::ctrl_c().await?;
+    tracing::info!("🛑 Shutdown signal received");
+    let _ = shutdown_tx.send(true);
+    shutdown_all_tasks().await;
+    tracing::info!("👋 Shutdown complete");
+
+    Ok(())
+}
+
+/// Start every engine of one chain. Each chain has its own providers,
+/// nonce manager, sled database and flash liquidator.
+async fn start_chain(
+    chain: Arc<ChainProfile>,
+    sqlite_pool: sqlx::SqlitePool,
+    shutdown_rx: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
     // 1. WebSocket Client: For high-speed data streaming (BlockWatcher)
-    let ws = Ws::connect(constants::RPC_URL.as_str()).await?;
+    let ws = Ws::connect(chain.rpc_url.as_str()).await?;
     let ws_provider = Provider::new(ws);
     let ws_client = Arc::new(ws_provider);
 
     // 2. HTTP Client: For execution (Bootstraps, Engines, Executors)
-    let http = Http::new(Url::parse(&*constants::RPC_URL_HTTP)?);
+    let http = Http::new(Url::parse(&chain.rpc_url_http)?);
     let http_provider = Provider::new(http);
     let http_provider_arc = Arc::new(http_provider);
 
     // Middleware Layer: Nonce Management
+    let wallet = chain.wallet();
     let nonce_manager = NonceManagerMiddleware::new(
         http_provider_arc.clone(), 
-        constants::WALLET.address()
+        wallet.address()
     );
 
     // Middleware Layer: Signer
     let http_client = Arc::new(SignerMiddleware::new(
         nonce_manager,
-        constants::WALLET.clone(),
+        wallet,
     ));
 
     // --- Communication Channels ---
-    let (shutdown_tx, shutdown_rx) = watch::channel(false);
     let (block_tx, block_rx) = broadcast::channel::<u64>(64);
-    let (aave_tx, aave_rx) = mpsc::channel::<AdminCmd>(64);
-    let (morpho_tx, morpho_rx) = mpsc::channel::<AdminCmd>(64);
-    let (comet_tx, comet_rx) = mpsc::channel::<AdminCmd>(64);
+    let (price_tx, price_rx) = mpsc::channel::<(u64, Address)>(256);
 
     // --- Database Setup ---
-    if let Some(parent) = Path::new(constants::SLED_PATH).parent() {
+    chain.verify(http_provider_arc.clone()).await?;
+
+    let sled_path = &chain.sled_path;
+    if let Some(parent) = Path::new(sled_path).parent() {
         if !parent.exists() {
             tracing::info!("Creating database directory at {:?}", parent);
             fs::create_dir_all(parent)?;
         }
     }
-    let sled_db = Arc::new(sled::open(constants::SLED_PATH)?);
-    let sqlite_pool = db::connect(&*constants::DATABASE_URL).await?;
+    let sled_db = Arc::new(sled::open(sled_path)?);
+    let bootstrap_state = Arc::new(BootstrapState::new(sled_db.clone())?);
 
-    // --- Setup Contracts & Watchlists ---
+    // --- Protocol Plugins ---
     // Use http_client for initial setup calls
-    let contracts = fetch_contracts(http_client.clone())?;
-    let w_lists = fetch_watchlists(sled_db)?;
+    let contracts = fetch_contracts(&chain, http_client.clone())?;
+    let plugins = protocol_plugins(chain.clone(), http_client.clone(), sled_db).await?;
 
     // --- Bootstraps (Using HTTP Client) ---
-    let bootstraps: Vec<Arc<dyn Bootstrap>> = vec![
-        Arc::new(AaveBootstrap::new(
-            contracts.aave.clone(),
-            w_lists.aave_watchlist.clone(),
-            w_lists.bootstrap_state.clone(),
-            http_client.clone(),
-        )),
-        Arc::new(MorphoBootstrap::new(
-            contracts.morpho.clone(),
-            w_lists.morpho_watchlist.clone(),
-            w_lists.bootstrap_state.clone(),
-            http_client.clone(),
-        )),
-        Arc::new(CompoundBootstrap::new(
-            contracts.comet.clone(),
-            w_lists.comet_watchlist.clone(),
-        
-        )),
-    ];
-
-    tracing::info!("Running bootstraps...");
+    tracing::info!("Running {} bootstraps...", chain.name);
+    let bootstraps = plugins.bootstraps(bootstrap_state);
     bootstrap_engine::BootstrapExecutor { bootstraps }.run_all().await?;
 
-    let morpho_fut = morpho::start_engine(
-        http_client.clone(),
-        shutdown_rx.clone(),
-        morpho_rx,
-        w_lists.morpho_watchlist.clone(),
-        contracts.flash_liq.clone(),
-        contracts.morpho.clone(),
-    );
+    let classifier = Arc::new(PositionClassifier::new(sqlite_pool.clone(), chain.chain_id));
 
-    let aave_fut = aave::start_engine(
-        http_client.clone(),
-        shutdown_rx.clone(),
-        aave_rx,
-        w_lists.aave_watchlist.clone(),
-        Arc::new(contracts.aave.clone()),
-    );
+    let engines = plugins.start(EngineContext {
+        shutdown_rx: shutdown_rx.clone(),
+        classifier: classifier.clone(),
+    }).await?;
 
-    let compound_fut = compound::start_engine(
-        http_client.clone(),
-        w_lists.comet_watchlist.clone(),
-        contracts.comet,
-        shutdown_rx.clone(),
-        comet_rx,
-    );
-    
-
-    let (morpho_res, aave_res, compound_res): (anyhow::Result<Arc<dyn Liquidator>>, anyhow::Result<Arc<dyn Liquidator>>, anyhow::Result<Arc<dyn Liquidator>>) = 
-        tokio::join!(morpho_fut, aave_fut, compound_fut);
-
-
-    let morpho_engine: Arc<dyn Liquidator> = morpho_res?;
-    let aave_engine: Arc<dyn Liquidator> = aave_res?;
-    let compound_engine: Arc<dyn Liquidator> = compound_res?;
+    let (liquidators, admin_txs): (Vec<_>, Vec<_>) = engines
+        .into_iter()
+        .map(|engine| ((engine.name.clone(), engine.liquidator), (engine.name, engine.admin_tx)))
+        .unzip();
 
     // --- Executor ---
-    let liquidators = vec![morpho_engine, aave_engine, compound_engine];
+    let price_watcher = price_watcher::PriceWatcher::new(
+        ws_client.clone(),
+        liquidators.iter().map(|(_, liquidator)| liquidator.clone()).collect(),
+        price_tx,
+        shutdown_rx.clone(),
+    );
     let executor = liquidation_executor::LiqExecutor::new(
         liquidators,
         block_rx.resubscribe(),
+        price_rx,
         shutdown_rx.clone(),
+        chain.live.subscribe(),
     );
 
     spawn_named_and_register("liquidation_executor", async move {
@@ -160,12 +184,10 @@ pub async fn start_liquidation_engines() -> anyhow::Result<()> {
 
     // --- Other Components ---
     let mut watchlist_pruner = WatchListPruner::new(
-        aave_tx.clone(),
-        morpho_tx.clone(),
-        comet_tx.clone(),
+        admin_txs,
         block_rx.resubscribe(),
         shutdown_rx.clone(),
-        constants::PRUNE_INTERVAL,
+        chain.live.subscribe(),
     );
     spawn_named_and_register("watchlist_pruner", async move {
         if let Err(e) = watchlist_pruner.start().await {
@@ -177,7 +199,8 @@ pub async fn start_liquidation_engines() -> anyhow::Result<()> {
     let profit_distributor = Arc::new(ProfitDistributor::new(
         http_client.clone(), 
         f_liq.clone(), 
-        sqlite_pool.clone()
+        sqlite_pool.clone(),
+        chain.clone(),
     ));
     spawn_named_and_register("profit_distributor", async move {
         if let Err(e) = profit_distributor.start().await {
@@ -185,11 +208,19 @@ pub async fn start_liquidation_engines() -> anyhow::Result<()> {
         }
     }).await;
 
+    let bad_debt_shutdown = shutdown_rx.clone();
+    spawn_named_and_register("bad_debt_reporter", async move {
+        if let Err(e) = classifier.start(bad_debt_shutdown).await {
+            tracing::error!("❌ Bad debt reporter failed: {:?}", e);
+        }
+    }).await;
+
     let liq_data_extractor = LiqDataExtractor::new(
         f_liq.clone(),
-        sqlite_pool.clone(),
+        sqlite_pool,
         shutdown_rx.clone(),
         http_client.clone(),
+        chain.chain_id,
     );
     spawn_named_and_register("liq_data_extractor", async move {
         if let Err(e) = liq_data_extractor.start().await {
@@ -209,12 +240,33 @@ pub async fn start_liquidation_engines() -> anyhow::Result<()> {
         }
     }).await;
 
-    tracing::info!("🚀 Liquidation system started");
-    tokio::signal::ctrl_c().await?;
-    tracing::info!("🛑 Shutdown signal received");
-    let _ = shutdown_tx.send(true);
-    shutdown_all_tasks().await;
-    tracing::info!("👋 Shutdown complete");
+    spawn_named_and_register("price_watcher", async move {
+        if let Err(e) = price_watcher.start().await {
+            tracing::error!("❌ Price watcher failed: {:?}", e);
+        }
+    }).await;
 
     Ok(())
+}
+
+/// Every lending protocol deployed on `chain`. A new protocol only needs a
+/// `ProtocolPlugin` implementation registered here.
+async fn protocol_plugins<M: Middleware + 'static>(
+    chain: Arc<ChainProfile>,
+    client: Arc<M>,
+    db: Arc<sled::Db>,
+) -> anyhow::Result<PluginRegistry> {
+    let mut registry = PluginRegistry::default();
+
+    if chain.morpho.is_some() {
+        registry.register(MorphoPlugin::load(chain.clone(), client.clone(), db.clone())?);
+    }
+    if chain.compound.is_some() {
+        registry.register(CompoundPlugin::load(chain.clone(), client.clone(), db.clone())?);
+    }
+    if chain.aave.is_some() {
+        registry.register(AavePlugin::load(chain, client, db).await?);
+    }
+
+    Ok(registry)
 }
\ No newline at end of file
@@ -20,6 +20,7 @@ pub struct LiqDataExtractor<M: Middleware + 'static> {
     db_pool: sqlx::SqlitePool,
     shutdown: watch::Receiver<bool>,
     provider: Arc<M>,
+    chain_id: u64,
 }
 
 impl<M: Middleware + 'static> LiqDataExtractor<M> {
@@ -28,12 +29,14 @@ impl<M: Middleware + 'static> LiqDataExtractor<M> {
         db_pool: sqlx::SqlitePool,
         shutdown: watch::Receiver<bool>,
         provider: Arc<M>,
+        chain_id: u64,
     ) -> Self {
         Self {
             flash_liquidator,
             db_pool,
             shutdown,
             provider,
+            chain_id,
         }
     }
 
@@ -112,6 +115,7 @@ impl<M: Middleware + 'static> LiqDataExtractor<M> {
         }.to_string();
 
         let record = LiquidationRecord {
+            chain_id: self.chain_id as i64,
             timestamp,
             tx_hash: tx_hash.to_string(),
             borrower,
@@ -143,6 +147,7 @@ impl<M: Middleware + 'static> LiqDataExtractor<M> {
         let amount = owner_share + breet_share;
 
         let record = DistributionRecord {
+            chain_id: self.chain_id as i64,
             timestamp,
             tx_hash: tx_hash.to_string(),
             asset: asset.to_string(),
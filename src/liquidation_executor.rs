@@ -1,49 +1,97 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use ethers::types::Address;
+use tokio::sync::{broadcast::Receiver, mpsc, watch};
+
+use crate::{common::Liquidator, settings::LiveSettings};
+
+/// Work queued for one liquidator. Triggers that arrive while it is busy
+/// are merged here and drained once the current run finishes.
+#[derive(Default)]
+struct Slot {
+    running: bool,
+    /// A per-block hook is still in flight
+    probing: bool,
+    /// Block of the last periodic cycle that actually started
+    last_cycle: u64,
+    /// Latest block a periodic cycle is waiting for
+    cycle: Option<u64>,
+    /// Feeds updated since the last targeted scan started
+    feeds: BTreeSet<Address>,
+    feeds_block: u64,
+}
 
-use tokio::sync::{broadcast::Receiver, watch, Mutex};
+enum Job {
+    Cycle(u64),
+    Feeds(u64, Vec<Address>),
+}
 
-use crate::{
-    common::Liquidator,
-    constants
+impl Slot {
+    fn has_work(&self) -> bool {
+        self.cycle.is_some() || !self.feeds.is_empty()
+    }
 
-};
+    /// Next job to run, periodic cycles first. Marks the slot idle when
+    /// nothing is left.
+    fn next(&mut self) -> Option<Job> {
+        if let Some(block_number) = self.cycle.take() {
+            self.last_cycle = block_number;
+            return Some(Job::Cycle(block_number));
+        }
+
+        if !self.feeds.is_empty() {
+            let feeds = std::mem::take(&mut self.feeds).into_iter().collect();
+            return Some(Job::Feeds(self.feeds_block, feeds));
+        }
+
+        self.running = false;
+        None
+    }
+}
 
 pub struct LiqExecutor {
-    liquidators: Vec<Arc<dyn Liquidator>>,
-    locks: Vec<Arc<Mutex<()>>>,
+    /// Liquidators by engine name (`<protocol>` or `<protocol>:<instance>`)
+    liquidators: Vec<(String, Arc<dyn Liquidator>)>,
+    slots: Vec<Arc<Mutex<Slot>>>,
     receiver: Receiver<u64>,
+    price_rx: mpsc::Receiver<(u64, Address)>,
     shutdown: watch::Receiver<bool>,
-    interval: u64,
+    /// Interval and enabled protocols, read on every block
+    live: watch::Receiver<LiveSettings>,
 }
 
 impl LiqExecutor {
     pub fn new(
-        liquidators: Vec<Arc<dyn Liquidator>>,
+        liquidators: Vec<(String, Arc<dyn Liquidator>)>,
         receiver: Receiver<u64>,
+        price_rx: mpsc::Receiver<(u64, Address)>,
         shutdown: watch::Receiver<bool>,
+        live: watch::Receiver<LiveSettings>,
     ) -> Self {
-        let locks = liquidators
+        let slots = liquidators
             .iter()
-            .map(|_| Arc::new(Mutex::new(())))
+            .map(|_| Arc::new(Mutex::new(Slot::default())))
             .collect();
 
         Self {
             liquidators,
-            locks,
+            slots,
             receiver,
+            price_rx,
             shutdown,
-            interval: constants::LIQ_EXECUTOR_INTERVAL,
+            live,
         }
     }
 
     pub async fn start(mut self) -> anyhow::Result<()> {
         tracing::info!(
             "📡 Liquidation executor started (every {} blocks)",
-            self.interval
+            self.live.borrow().tuning.liq_executor_interval
         );
 
-        let mut last_run_block = 0u64;
-
         loop {
             tokio::select! {
                 // 🔴 Shutdown signal
@@ -66,51 +114,46 @@ impl LiqExecutor {
                         }
                     };
 
-                    // Deterministic debouncing
-                    if block_number <= last_run_block {
-                        continue;
-                    }
+                    self.probe(block_number);
+
+                    // A cycle still waiting is moved up to the newest block
+                    let interval = self.live.borrow().tuning.liq_executor_interval;
+                    let queued = self.dispatch(|slot| {
+                        if block_number < slot.last_cycle + interval {
+                            return false;
+                        }
+                        slot.cycle = Some(block_number);
+                        true
+                    });
 
-                    if block_number < last_run_block + self.interval {
-                        tracing::trace!(
-                            "⏭️  Skipping block {} (last run {})",
+                    if queued > 0 {
+                        tracing::info!(
+                            "🚀 Liquidation cycle triggered at block {} ({} liquidators)",
                             block_number,
-                            last_run_block
+                            queued
                         );
-                        continue;
                     }
+                }
 
-                    last_run_block = block_number;
+                // 📈 Price feed update, scan only the positions it reprices
+                Some((block_number, feed)) = self.price_rx.recv() => {
+                    // Feeds updated in the same round arrive together, cover them in one scan
+                    let mut feeds = BTreeSet::from([feed]);
+                    while let Ok((_, f)) = self.price_rx.try_recv() {
+                        feeds.insert(f);
+                    }
 
                     tracing::info!(
-                        "🚀 Liquidation cycle triggered at block {}",
+                        "📈 {} price feed(s) updated at block {}, triggering targeted scan",
+                        feeds.len(),
                         block_number
                     );
 
-                    for (liq, lock) in self
-                        .liquidators
-                        .iter()
-                        .cloned()
-                        .zip(self.locks.iter().cloned())
-                    {
-                        tokio::spawn(async move {
-                            let guard = match lock.try_lock() {
-                                Ok(g) => g,
-                                Err(_) => {
-                                    tracing::debug!(
-                                        "⏳ Liquidator already running, skipping this cycle"
-                                    );
-                                    return;
-                                }
-                            };
-
-                            if let Err(e) = liq.run(block_number).await {
-                                tracing::error!("❌ Liquidator failed: {:?}", e);
-                            }
-
-                            drop(guard);
-                        });
-                    }
+                    self.dispatch(|slot| {
+                        slot.feeds.extend(feeds.iter().copied());
+                        slot.feeds_block = slot.feeds_block.max(block_number);
+                        true
+                    });
                 }
             }
         }
@@ -118,4 +161,87 @@ impl LiqExecutor {
         tracing::info!("✅ Liquidation executor stopped cleanly");
         Ok(())
     }
+
+    /// Queue work on every enabled liquidator and start a worker for the
+    /// idle ones. Returns how many liquidators `queue` added work to.
+    fn dispatch(&self, queue: impl Fn(&mut Slot) -> bool) -> usize {
+        let tuning = self.live.borrow().tuning.clone();
+        let mut queued = 0;
+
+        for ((name, liq), slot) in self.liquidators.iter().zip(&self.slots) {
+            let protocol = name.split(':').next().unwrap_or_default();
+            if !tuning.is_enabled(protocol) {
+                tracing::trace!("⏸️ {} is disabled, skipping", name);
+                continue;
+            }
+
+            let start = {
+                let mut state = slot.lock().unwrap_or_else(PoisonError::into_inner);
+                if !queue(&mut state) {
+                    continue;
+                }
+                queued += 1;
+
+                if state.running {
+                    tracing::debug!("⏳ {} is busy, work queued for after the current run", name);
+                }
+                let idle = !state.running && state.has_work();
+                state.running |= idle;
+                idle
+            };
+
+            if start {
+                tokio::spawn(Self::drain(name.clone(), liq.clone(), slot.clone()));
+            }
+        }
+
+        queued
+    }
+
+    /// Run every enabled liquidator's per-block hook. A hook still running
+    /// from an earlier block is not stacked.
+    fn probe(&self, block_number: u64) {
+        let tuning = self.live.borrow().tuning.clone();
+
+        for ((name, liq), slot) in self.liquidators.iter().zip(&self.slots) {
+            let protocol = name.split(':').next().unwrap_or_default();
+            if !tuning.is_enabled(protocol) {
+                continue;
+            }
+
+            {
+                let mut state = slot.lock().unwrap_or_else(PoisonError::into_inner);
+                if state.probing {
+                    tracing::debug!("⏳ {} block hook still running, skipping block {}", name, block_number);
+                    continue;
+                }
+                state.probing = true;
+            }
+
+            let (name, liq, slot) = (name.clone(), liq.clone(), slot.clone());
+            tokio::spawn(async move {
+                if let Err(e) = liq.on_block(block_number).await {
+                    tracing::warn!("⚠️  {} block hook failed at block {}: {:?}", name, block_number, e);
+                }
+                slot.lock().unwrap_or_else(PoisonError::into_inner).probing = false;
+            });
+        }
+    }
+
+    /// Run a liquidator's queued jobs until its slot is empty
+    async fn drain(name: String, liq: Arc<dyn Liquidator>, slot: Arc<Mutex<Slot>>) {
+        loop {
+            let job = slot.lock().unwrap_or_else(PoisonError::into_inner).next();
+
+            let result = match job {
+                Some(Job::Cycle(block_number)) => liq.run(block_number).await,
+                Some(Job::Feeds(block_number, feeds)) => liq.run_for_feeds(block_number, &feeds).await,
+                None => break,
+            };
+
+            if let Err(e) = result {
+                tracing::error!("❌ Liquidator {} failed: {:?}", name, e);
+            }
+        }
+    }
 }
@@ -16,3 +16,36 @@ abigen!(
     ]"#
 );
 
+abigen!(
+    IChainlinkOracle,
+    r#"[
+        function BASE_FEED_1() external view returns (address)
+        function BASE_FEED_2() external view returns (address)
+        function QUOTE_FEED_1() external view returns (address)
+        function QUOTE_FEED_2() external view returns (address)
+    ]"#
+);
+
+abigen!(
+    IIrm,
+    r#"[
+        struct IrmMarketParams { address loanToken; address collateralToken; address oracle; address irm; uint256 lltv; }
+        struct IrmMarket { uint128 totalSupplyAssets; uint128 totalSupplyShares; uint128 totalBorrowAssets; uint128 totalBorrowShares; uint128 lastUpdate; uint128 fee; }
+        function borrowRateView(IrmMarketParams marketParams, IrmMarket market) external view returns (uint256)
+    ]"#
+);
+
+abigen!(
+    IPreLiquidationFactory,
+    r#"[
+        struct PreLiquidationParams { uint256 preLltv; uint256 preLCF1; uint256 preLCF2; uint256 preLIF1; uint256 preLIF2; address preLiquidationOracle; }
+        event CreatePreLiquidation(address indexed preLiquidation, bytes32 id, PreLiquidationParams preLiquidationParams)
+    ]"#
+);
+
+abigen!(
+    IPreLiquidation,
+    r#"[
+        function preLiquidate(address borrower, uint256 seizedAssets, uint256 repaidShares, bytes data) external returns (uint256, uint256)
+    ]"#
+);
@@ -1,14 +1,17 @@
 
 use ethers::{
     providers::Middleware,
-    types::{H256, Address}
+    types::{H256, Address, U256}
 };
 
+use anyhow::anyhow;
 use std::sync::Arc;
 
 use super::{
     morpho_config::MorphoConfig,
-    abi_bindings::IMorphoBlue
+    abi_bindings::{IIrm, IMorphoBlue, IrmMarket, IrmMarketParams, MarketParams},
+    morpho_math::{accrued_interest, checked_add},
+    types::Market,
 };
 
 use crate::common::{abi_bindings::IFlashLiquidator};
@@ -34,4 +37,51 @@ pub async fn has_outstanding_debt<M: Middleware + 'static>(
         morpho.position(market, borrower).call().await?;
 
     Ok(borrow_shares != 0)
-}
\ No newline at end of file
+}
+
+/// Market borrow totals with interest accrued up to `now`, as
+/// `MorphoBalancesLib.expectedMarketBalances` does
+pub async fn expected_market<M: Middleware + 'static>(
+    morpho: &IMorphoBlue<M>,
+    market_id: [u8; 32],
+    params: &MarketParams,
+    now: u64,
+) -> anyhow::Result<Market> {
+    let market = morpho.market(market_id).call().await?;
+    let (_, _, total_borrow_assets, total_borrow_shares, last_update, _) = market;
+
+    let elapsed = now.saturating_sub(last_update as u64);
+    if elapsed == 0 || params.irm.is_zero() || total_borrow_assets == 0 {
+        return Ok(Market {
+            total_borrow_assets,
+            total_borrow_shares,
+        });
+    }
+
+    let irm = IIrm::new(params.irm, morpho.client());
+    let irm_params = IrmMarketParams {
+        loan_token: params.loan_token,
+        collateral_token: params.collateral_token,
+        oracle: params.oracle,
+        irm: params.irm,
+        lltv: params.lltv,
+    };
+    let irm_market = IrmMarket {
+        total_supply_assets: market.0,
+        total_supply_shares: market.1,
+        total_borrow_assets,
+        total_borrow_shares,
+        last_update,
+        fee: market.5,
+    };
+    let borrow_rate = irm.borrow_rate_view(irm_params, irm_market).call().await?;
+
+    let interest = accrued_interest(U256::from(total_borrow_assets), borrow_rate, elapsed)?;
+    let accrued = checked_add(U256::from(total_borrow_assets), interest)?;
+
+    Ok(Market {
+        total_borrow_assets: u128::try_from(accrued)
+            .map_err(|_| anyhow!("accrued borrow assets {} exceed uint128", accrued))?,
+        total_borrow_shares,
+    })
+}
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use dashmap::DashMap;
+use ethers::{
+    providers::Middleware,
+    types::{Address, BlockNumber, H256, U256},
+};
+use futures_util::{stream, StreamExt};
+use tokio::sync::Mutex;
+
+use super::{
+    abi_bindings::{IMorphoBlue, IOracle, MarketParams},
+    helpers,
+    types::Market,
+};
+
+/// Everything a borrower analysis reads about its market
+#[derive(Clone)]
+pub struct MarketSnapshot {
+    pub params: MarketParams,
+    pub market: Market,
+    pub price: U256,
+}
+
+/// Market reads shared by all borrower analyses of a block.
+///
+/// `MarketParams` never change and are kept for the process lifetime;
+/// market state and oracle prices are dropped when a newer block starts.
+pub struct MarketCache<M> {
+    morpho: IMorphoBlue<M>,
+    params: DashMap<H256, MarketParams>,
+    /// (block, timestamp) the per-block entries belong to
+    cycle: Mutex<(u64, u64)>,
+    states: DashMap<H256, Market>,
+    prices: DashMap<Address, U256>,
+}
+
+impl<M: Middleware + 'static> MarketCache<M> {
+    pub fn new(morpho: IMorphoBlue<M>) -> Self {
+        Self {
+            morpho,
+            params: DashMap::new(),
+            cycle: Mutex::new((0, 0)),
+            states: DashMap::new(),
+            prices: DashMap::new(),
+        }
+    }
+
+    pub async fn params(&self, market_id: H256) -> anyhow::Result<MarketParams> {
+        if let Some(params) = self.params.get(&market_id) {
+            return Ok(params.clone());
+        }
+
+        let (loan_token, collateral_token, oracle, irm, lltv) = self
+            .morpho
+            .id_to_market_params(market_id.to_fixed_bytes())
+            .call()
+            .await?;
+        let params = MarketParams { loan_token, collateral_token, oracle, irm, lltv };
+
+        self.params.insert(market_id, params.clone());
+        Ok(params)
+    }
+
+    /// Start the cycle of `block` and fetch each market once, concurrently
+    pub async fn prepare(&self, block: u64, markets: &HashSet<H256>) -> anyhow::Result<()> {
+        {
+            let mut cycle = self.cycle.lock().await;
+            if block > cycle.0 {
+                let timestamp = self
+                    .morpho
+                    .client()
+                    .get_block(BlockNumber::Number(block.into()))
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("block {} not found", block))?
+                    .timestamp
+                    .as_u64();
+
+                *cycle = (block, timestamp);
+                self.states.clear();
+                self.prices.clear();
+            }
+        }
+
+        stream::iter(markets.iter().copied())
+            .for_each_concurrent(8, |market_id| async move {
+                if let Err(e) = self.snapshot(market_id).await {
+                    tracing::warn!("Failed to snapshot Morpho market {:?}: {:?}", market_id, e);
+                }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Market state and oracle price for the current cycle
+    pub async fn snapshot(&self, market_id: H256) -> anyhow::Result<MarketSnapshot> {
+        let params = self.params(market_id).await?;
+
+        let market = match self.states.get(&market_id).map(|m| *m) {
+            Some(market) => market,
+            None => {
+                let now = self.cycle.lock().await.1;
+                let market = helpers::expected_market(&self.morpho, market_id.to_fixed_bytes(), &params, now).await?;
+                self.states.insert(market_id, market);
+                market
+            }
+        };
+
+        let price = self.price(params.oracle).await?;
+
+        Ok(MarketSnapshot { params, market, price })
+    }
+
+    /// Oracle price, read once per cycle
+    pub async fn price(&self, oracle: Address) -> anyhow::Result<U256> {
+        if let Some(price) = self.prices.get(&oracle) {
+            return Ok(*price);
+        }
+
+        let price = IOracle::new(oracle, self.morpho.client()).price().call().await?;
+        self.prices.insert(oracle, price);
+        Ok(price)
+    }
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashSet, sync::{Arc, RwLock}};
+
+use anyhow::Result;
+use dashmap::{DashMap, DashSet};
+use ethers::{
+    providers::Middleware,
+    types::{Address, H256, U256},
+};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use sled::{Db, Tree};
+
+use super::abi_bindings::IMorphoBlue;
+
+use crate::{chain_profile::MorphoDeployment, common::get_token_decimals, settings::LiveSettings};
+
+/// `MarketParams` of a market as emitted by `CreateMarket`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MarketInfo {
+    pub loan_token: Address,
+    pub collateral_token: Address,
+    pub oracle: Address,
+    pub irm: Address,
+    pub lltv: U256,
+    pub created_block: u64,
+}
+
+/// Which discovered markets we act on
+#[derive(Debug, Clone)]
+pub struct MarketRules {
+    /// Empty means any loan token
+    pub loan_tokens: HashSet<Address>,
+    /// Empty means any collateral token
+    pub collateral_tokens: HashSet<Address>,
+    /// Empty means any market
+    pub markets: HashSet<H256>,
+    pub min_lltv: U256,
+    pub max_lltv: U256,
+    /// Whole loan tokens, scaled by the token's decimals
+    pub min_total_borrow: u64,
+}
+
+impl MarketRules {
+    pub fn load(deployment: &MorphoDeployment) -> Self {
+        // LLTV is WAD scaled, 1 bps = 1e14
+        let bps = U256::exp10(14);
+        Self {
+            loan_tokens: deployment.loan_tokens.clone(),
+            collateral_tokens: deployment.collateral_tokens.clone(),
+            markets: deployment.markets.clone(),
+            min_lltv: U256::from(deployment.min_lltv_bps) * bps,
+            max_lltv: U256::from(deployment.max_lltv_bps) * bps,
+            min_total_borrow: deployment.min_total_borrow,
+        }
+    }
+
+    fn matches(&self, id: &H256, info: &MarketInfo) -> bool {
+        (self.markets.is_empty() || self.markets.contains(id))
+            && (self.loan_tokens.is_empty() || self.loan_tokens.contains(&info.loan_token))
+            && (self.collateral_tokens.is_empty() || self.collateral_tokens.contains(&info.collateral_token))
+            && info.lltv >= self.min_lltv
+            && info.lltv <= self.max_lltv
+    }
+}
+
+/// Morpho Blue markets discovered from `CreateMarket`.
+///
+/// Maintains:
+///     market id → MarketInfo          ("morpho:markets")
+///     market ids already backfilled   ("morpho:indexed_markets")
+///
+/// The selection is re-evaluated against the rules on startup, on every
+/// prune, since total borrow moves, and when a reload changes the rules.
+pub struct MarketRegistry {
+    db: Arc<Tree>,
+    indexed_db: Arc<Tree>,
+    markets: DashMap<H256, MarketInfo>,
+    selected: DashSet<H256>,
+    indexed: DashSet<H256>,
+    rules: RwLock<MarketRules>,
+}
+
+impl MarketRegistry {
+    pub fn new(db: Arc<Db>, rules: MarketRules) -> Result<Self> {
+        let tree = db.open_tree("morpho:markets")?;
+        let indexed_tree = db.open_tree("morpho:indexed_markets")?;
+
+        let markets = DashMap::new();
+        for item in tree.iter() {
+            let (k, v) = item?;
+            markets.insert(H256::from_slice(&k), bincode::deserialize(&v)?);
+        }
+
+        let indexed = DashSet::new();
+        for item in indexed_tree.iter() {
+            let (k, _) = item?;
+            indexed.insert(H256::from_slice(&k));
+        }
+
+        Ok(Self {
+            db: Arc::new(tree),
+            indexed_db: Arc::new(indexed_tree),
+            markets,
+            selected: DashSet::new(),
+            indexed,
+            rules: RwLock::new(rules),
+        })
+    }
+
+    /// Register a market, returns true if it was not known before
+    pub async fn register(&self, id: H256, info: MarketInfo) -> Result<bool> {
+        if self.markets.insert(id, info).is_some() {
+            return Ok(false);
+        }
+
+        let db = self.db.clone();
+        let encoded = bincode::serialize(&info)?;
+        tokio::task::spawn_blocking(move || db.insert(id.as_bytes(), encoded)).await??;
+
+        Ok(true)
+    }
+
+    /// Whether the market is selected by the rules
+    pub fn contains(&self, id: &H256) -> bool {
+        self.selected.contains(id)
+    }
+
+    pub fn info(&self, id: &H256) -> Option<MarketInfo> {
+        self.markets.get(id).map(|m| *m)
+    }
+
+    pub fn selected(&self) -> HashSet<H256> {
+        self.selected.iter().map(|id| *id).collect()
+    }
+
+    /// Selected markets whose borrowers were never backfilled
+    pub fn unindexed(&self) -> HashSet<H256> {
+        self.selected
+            .iter()
+            .map(|id| *id)
+            .filter(|id| !self.indexed.contains(id))
+            .collect()
+    }
+
+    pub async fn mark_indexed(&self, ids: &HashSet<H256>) -> Result<()> {
+        let fresh: Vec<H256> = ids.iter().copied().filter(|id| self.indexed.insert(*id)).collect();
+        if fresh.is_empty() {
+            return Ok(());
+        }
+
+        let db = self.indexed_db.clone();
+        tokio::task::spawn_blocking(move || {
+            for id in fresh {
+                db.insert(id.as_bytes(), &[])?;
+            }
+            db.flush()?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Swap the token and market filters of the rules, the selection
+    /// follows on the next `refresh_selection`
+    pub fn set_filters(&self, live: &LiveSettings) {
+        let mut rules = self.rules.write().unwrap_or_else(|e| e.into_inner());
+        rules.loan_tokens = live.morpho_loan_tokens.clone();
+        rules.collateral_tokens = live.morpho_collateral_tokens.clone();
+        rules.markets = live.morpho_markets.clone();
+    }
+
+    /// Re-apply the rules to every known market
+    pub async fn refresh_selection<M: Middleware + 'static>(&self, morpho: &IMorphoBlue<M>) -> Result<()> {
+        let ids: Vec<H256> = self.markets.iter().map(|m| *m.key()).collect();
+
+        stream::iter(ids)
+            .for_each_concurrent(8, |id| async move {
+                if let Err(e) = self.evaluate(id, morpho).await {
+                    tracing::warn!("Failed to evaluate Morpho market {:?}: {:?}", id, e);
+                }
+            })
+            .await;
+
+        tracing::info!(
+            "Morpho market registry: {} known, {} selected",
+            self.markets.len(),
+            self.selected.len()
+        );
+
+        Ok(())
+    }
+
+    /// Apply the rules to one market, returns whether it is selected
+    pub async fn evaluate<M: Middleware + 'static>(&self, id: H256, morpho: &IMorphoBlue<M>) -> Result<bool> {
+        let Some(info) = self.info(&id) else {
+            return Ok(false);
+        };
+
+        let min_total_borrow = {
+            let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+            rules.matches(&id, &info).then_some(rules.min_total_borrow)
+        };
+        let Some(min_total_borrow) = min_total_borrow else {
+            self.selected.remove(&id);
+            return Ok(false);
+        };
+
+        let (_, _, total_borrow_assets, _, _, _) = morpho.market(id.to_fixed_bytes()).call().await?;
+        let decimals = get_token_decimals(info.loan_token, morpho.client()).await?;
+        let min_borrow = U256::from(min_total_borrow) * U256::exp10(decimals as usize);
+
+        let selected = U256::from(total_borrow_assets) >= min_borrow;
+        if selected {
+            self.selected.insert(id);
+        } else {
+            self.selected.remove(&id);
+        }
+
+        Ok(selected)
+    }
+}
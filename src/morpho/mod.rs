@@ -1,49 +1,83 @@
 use ethers::providers::Middleware;
+use sled::Db;
 use std::sync::Arc;
 
 pub mod abi_bindings;
 pub mod helpers;
+pub mod market_cache;
+pub mod market_registry;
 pub mod morpho_config;
 pub mod morpho_liquidator;
 pub mod morpho_math;
 pub mod morpho_watchlist;
+pub mod plugin;
+pub mod pre_liquidation;
+pub mod repay_optimizer;
 pub mod types;
 pub mod watchlist_updater;
 
+use market_registry::{MarketRegistry, MarketRules};
 use morpho_config::MorphoConfig;
 use morpho_liquidator::MorphoLiquidator;
 use morpho_watchlist::MorphoWatchList;
+use pre_liquidation::{PreLiquidationIndexer, PreLiquidationRegistry};
 use watchlist_updater::WatchListUpdater;
 
 use crate::{
     common::{
-        abi_bindings::IFlashLiquidator, 
         task_manager::spawn_named_and_register, 
         AdminCmd, 
         Config,
         Liquidator,
     },
-    morpho::abi_bindings::IMorphoBlue,
+    chain_profile::ChainProfile,
+    morpho::abi_bindings::IPreLiquidationFactory,
+    position_classifier::PositionClassifier,
 };
 
 use tokio::sync::{mpsc, watch};
 
+/// State of the Morpho engine
+pub struct MorphoInstance {
+    pub config: Arc<MorphoConfig>,
+    pub watch_list: Arc<MorphoWatchList>,
+    pub markets: Arc<MarketRegistry>,
+    pub pre_liquidations: Arc<PreLiquidationRegistry>,
+}
+
+pub fn load_instance(chain: Arc<ChainProfile>, db: Arc<Db>) -> anyhow::Result<MorphoInstance> {
+    let config = match MorphoConfig::load(chain) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            tracing::error!("❌ Failed to load Morpho config: {:?}", e);
+            return Err(anyhow::anyhow!("Failed to load Morpho config"));
+        }
+    };
+    let rules = config
+        .chain
+        .morpho
+        .as_ref()
+        .map(MarketRules::load)
+        .ok_or_else(|| anyhow::anyhow!("Morpho is not deployed on {}", config.chain.name))?;
+
+    Ok(MorphoInstance {
+        config,
+        watch_list: Arc::new(MorphoWatchList::new(db.clone())?),
+        markets: Arc::new(MarketRegistry::new(db.clone(), rules)?),
+        pre_liquidations: Arc::new(PreLiquidationRegistry::new(db)?),
+    })
+}
+
 pub async fn start_engine<M: Middleware + 'static>(
     client: Arc<M>,
     shutdown_rx: watch::Receiver<bool>,
     prune_rx: mpsc::Receiver<AdminCmd>,
-    watch_list: Arc<MorphoWatchList>,
-    f_liq: IFlashLiquidator<M>,
-    morpho: IMorphoBlue<M>,
+    instance: MorphoInstance,
+    classifier: Arc<PositionClassifier>,
 ) -> anyhow::Result<Arc<dyn Liquidator>> {
+    let MorphoInstance { config, watch_list, markets, pre_liquidations } = instance;
 
-     let config = match MorphoConfig::load() {
-            Ok(c) => Arc::new(c),
-            Err(e) => {
-                tracing::error!("❌ Failed to load Morpho config: {:?}", e);
-                return Err(anyhow::anyhow!("Failed to load Morpho config"));
-            }
-        };
+    let (morpho, f_liq) = helpers::fetch_contracts(client.clone(), config.clone());
    
     let morpho_liq = Arc::new(MorphoLiquidator::new(
         morpho.clone(),
@@ -51,17 +85,35 @@ pub async fn start_engine<M: Middleware + 'static>(
         watch_list.clone(),
         client.clone(),
         config.clone(),
+        classifier,
+        pre_liquidations.clone(),
     ));
 
+    if let Some(factory) = config.pre_liquidation_factory {
+        let indexer = PreLiquidationIndexer::new(
+            pre_liquidations,
+            IPreLiquidationFactory::new(factory, client.clone()),
+            morpho.clone(),
+            shutdown_rx.clone(),
+        );
+
+        spawn_named_and_register("morpho_pre_liquidation_indexer", async move {
+            if let Err(e) = indexer.start().await {
+                tracing::error!("❌ Morpho pre-liquidation indexer failed: {:?}", e);
+            }
+        }).await;
+    }
+
     spawn_named_and_register("morpho_watchlist_updater", async move {
         tracing::info!("Morpho watch list updater starting...");
 
         let updater = WatchListUpdater::new(
             watch_list.clone(),
+            markets,
             Arc::new(morpho),
-            config.clone(),
             shutdown_rx,
-            prune_rx
+            prune_rx,
+            config.chain.live.subscribe(),
         );
 
         if let Err(e) = updater.start().await {
@@ -1,45 +1,53 @@
-use std::collections::HashSet;
-use ethers::{signers::{LocalWallet, Signer}, types::{Address, H256, U256}};
+use std::sync::Arc;
 
-use crate::{common::Config, constants};
+use anyhow::anyhow;
+use ethers::{signers::{LocalWallet, Signer}, types::{Address, U256}};
+
+use crate::{chain_profile::ChainProfile, common::Config, constants};
 
 pub struct MorphoConfig {
     pub morpho_blue: Address,
     pub flash_liquidator: Address,
-    pub morpho_markets: HashSet<H256>,
     pub rpc_url: String,
     pub wallet: LocalWallet,
     pub db_path: String,
     pub block_interval: u64,
     pub keeper_address: Address,
-    pub chain_id: u64,
-    pub oracle_price_scale: U256
-
+    pub chain: Arc<ChainProfile>,
+    pub oracle_price_scale: U256,
+    pub deploy_block: u64,
+    pub pre_liquidation_factory: Option<Address>,
+    pub pre_liquidation_factory_block: u64,
 }
 
 impl Config for MorphoConfig {
-     fn load() -> anyhow::Result<Self> {
-        let morpho_blue: Address = *constants::MORPHO_BLUE;
-        let flash_liquidator: Address = *constants::FLASH_LIQUIDATOR;
-        let morpho_markets = constants::MORPHO_MARKETS.clone();
-        let rpc_url = constants::RPC_URL.clone();
-        let wallet = constants::WALLET.clone();
-        let db_path = constants::SLED_PATH.into();
-        let block_interval = constants::LIQ_EXECUTOR_INTERVAL;
+     fn load(chain: Arc<ChainProfile>) -> anyhow::Result<Self> {
+        let deployment = chain
+            .morpho
+            .clone()
+            .ok_or_else(|| anyhow!("Morpho is not deployed on {}", chain.name))?;
+
+        let morpho_blue: Address = deployment.morpho_blue;
+        let flash_liquidator: Address = chain.flash_liquidator;
+        let rpc_url = chain.rpc_url.clone();
+        let wallet = chain.wallet();
+        let db_path = chain.sled_path.clone();
+        let block_interval = chain.tuning().liq_executor_interval;
         let keeper_address = wallet.address();
-        let chain_id = constants::CHAIN_ID;
         let oracle_price_scale = constants::ORACLE_PRICE_SCALE.clone();
         Ok(Self { 
             morpho_blue, 
             flash_liquidator, 
-            morpho_markets, 
             rpc_url, 
             wallet,
             db_path,
             block_interval,
             keeper_address,
-            chain_id,
-            oracle_price_scale
+            chain,
+            oracle_price_scale,
+            deploy_block: deployment.deploy_block,
+            pre_liquidation_factory: deployment.pre_liquidation_factory,
+            pre_liquidation_factory_block: deployment.pre_liquidation_factory_block,
         })
         
     }
@@ -48,7 +56,7 @@ impl Config for MorphoConfig {
         self.keeper_address
     }
     fn chain_id(&self) -> u64 {
-        self.chain_id
+        self.chain.chain_id
     }
       
-}
\ No newline at end of file
+}
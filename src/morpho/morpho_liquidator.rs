@@ -1,31 +1,40 @@
 use anyhow::ensure;
+use dashmap::DashMap;
 use ethers::{
     providers::Middleware, 
-    types::{Address, H256, U256}
+    types::{Address, Bytes, TransactionRequest, H256, U256},
+    utils::format_units,
 };
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use futures_util::stream::{self, StreamExt};
 
 use super::{
-    abi_bindings::{IMorphoBlue, IOracle, MarketParams},
+    abi_bindings::{IChainlinkOracle, IMorphoBlue, IPreLiquidation, MarketParams},
     morpho_math::*,
     morpho_config::MorphoConfig,
+    market_cache::{MarketCache, MarketSnapshot},
     morpho_watchlist::MorphoWatchList,
+    pre_liquidation::PreLiquidationRegistry,
+    repay_optimizer::{self, RepayTerms},
     types::{LiqCandidate,Market, Position, HealthCheck, LiquidationMode},
 };
 
 use crate::{common::{
     Liquidator, 
     SwapQueryParams, 
-    abi_bindings::{IFlashLiquidator, LiquidationParams}, 
+    abi_bindings::{IERC20, IFlashLiquidator, LiquidationParams}, 
     create_simulation_sandbox, 
+    ensure_mined,
     execute_liq_tx, 
     get_token_decimals, 
     paraswap::ParaSwapClient, 
+    resolve_price_aggregator,
     simulate_liq_tx, 
     simulation_sandbox::AnvilSandbox
-}};
+}, constants::PRE_LIQUIDATION_REPAY_BUFFER_BPS, position_classifier::{PositionClass, PositionClassifier, PositionSnapshot}};
+
+const MORPHO: &str = "Morpho";
 
 /// ─────────────────────────────────────────────
 /// Liquidation mode (Morpho invariant enforced)
@@ -38,6 +47,11 @@ pub struct MorphoLiquidator<M: Middleware> {
     pub flash_liquidator: IFlashLiquidator<M>,
     pub client: Arc<M>,
     pub config: Arc<MorphoConfig>,
+    pub classifier: Arc<PositionClassifier>,
+    pub pre_liquidations: Arc<PreLiquidationRegistry>,
+    /// market → Chainlink aggregators behind its oracle
+    market_feeds: DashMap<H256, Vec<Address>>,
+    markets: MarketCache<M>,
 }
 
 impl<M: Middleware + 'static> MorphoLiquidator<M> {
@@ -47,20 +61,26 @@ impl<M: Middleware + 'static> MorphoLiquidator<M> {
         watch_list: Arc<MorphoWatchList>,
         client: Arc<M>,
         config: Arc<MorphoConfig>,
+        classifier: Arc<PositionClassifier>,
+        pre_liquidations: Arc<PreLiquidationRegistry>,
     ) -> Self {
         Self {
+            markets: MarketCache::new(morpho_blue.clone()),
             watch_list,
             morpho_blue,
             flash_liquidator,
             client,
             config,
+            classifier,
+            pre_liquidations,
+            market_feeds: DashMap::new(),
         }
     }
 
     /// ─────────────────────────────────────────────
     /// Scan watchlist → produce liquidation candidates
     /// ─────────────────────────────────────────────
-    pub async fn generate_liquidations(&self) -> anyhow::Result<Vec<LiqCandidate>> {
+    pub async fn generate_liquidations(&self, block_number: u64) -> anyhow::Result<Vec<LiqCandidate>> {
     let snapshot = self.watch_list.snapshot();
 
     if snapshot.is_empty() {
@@ -68,10 +88,45 @@ impl<M: Middleware + 'static> MorphoLiquidator<M> {
         return Ok(vec![]);
     }
 
-    tracing::info!("Morpho Liquidator: Checking {} borrowers", snapshot.len());
+    // Recently hopeless positions wait for the recheck interval
+    let total = snapshot.len();
+    let snapshot: Vec<(Address, H256)> = snapshot
+        .into_iter()
+        .filter(|(borrower, market_id)| !self.classifier.is_hopeless(MORPHO, *market_id, *borrower))
+        .collect();
+
+    tracing::info!("Morpho Liquidator: Checking {} of {} borrowers", snapshot.len(), total);
+
+    self.analyze_entries(block_number, snapshot).await
+}
+
+/// Re-check only positions in markets whose oracle reads an updated feed
+async fn generate_for_feeds(&self, block_number: u64, feeds: &[Address]) -> anyhow::Result<Vec<LiqCandidate>> {
+    let entries: Vec<(Address, H256)> = self.watch_list
+        .snapshot()
+        .into_iter()
+        .filter(|(_, market_id)| {
+            self.market_feeds
+                .get(market_id)
+                .is_some_and(|f| f.iter().any(|feed| feeds.contains(feed)))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Ok(vec![]);
+    }
+
+    tracing::info!("Morpho Liquidator: Price update, checking {} positions", entries.len());
+
+    self.analyze_entries(block_number, entries).await
+}
 
+async fn analyze_entries(&self, block_number: u64, entries: Vec<(Address, H256)>) -> anyhow::Result<Vec<LiqCandidate>> {
+    // Each market is read once for the block, not once per borrower
+    let markets: HashSet<H256> = entries.iter().map(|(_, market_id)| *market_id).collect();
+    self.markets.prepare(block_number, &markets).await?;
 
-    let results: Vec<_> = stream::iter(snapshot)
+    let candidates = stream::iter(entries)
         .map(|(borrower, market_id)| async move {
             self.analyze_borrower(borrower, market_id.to_fixed_bytes()).await
         })
@@ -90,7 +145,31 @@ impl<M: Middleware + 'static> MorphoLiquidator<M> {
         .collect()
         .await;
 
-    Ok(results)
+    Ok(candidates)
+}
+
+/// Chainlink aggregators read by a `MorphoChainlinkOracleV2`; other
+/// oracle types yield none and only get the per-block scan
+async fn oracle_feeds(&self, oracle: Address) -> Vec<Address> {
+    let oracle = IChainlinkOracle::new(oracle, self.client.clone());
+
+    let base_1 = oracle.base_feed_1();
+    let base_2 = oracle.base_feed_2();
+    let quote_1 = oracle.quote_feed_1();
+    let quote_2 = oracle.quote_feed_2();
+
+    let proxies = match tokio::try_join!(base_1.call(), base_2.call(), quote_1.call(), quote_2.call()) {
+        Ok((b1, b2, q1, q2)) => vec![b1, b2, q1, q2],
+        Err(_) => return vec![],
+    };
+
+    let mut feeds = vec![];
+    for proxy in proxies {
+        if let Some(aggregator) = resolve_price_aggregator(proxy, self.client.clone()).await {
+            feeds.push(aggregator);
+        }
+    }
+    feeds
 }
 
 async fn analyze_borrower(
@@ -107,102 +186,220 @@ async fn analyze_borrower(
             return Ok(None);
         }
         
-        let (_, _, total_borrow_assets, total_borrow_shares, _, _) =
-            self.morpho_blue.market(market_id).call().await?;
-
-        let (loan_token, collateral_token, oracle_addr, _, lltv) =
-            self.morpho_blue.id_to_market_params(market_id).call().await?;
-
-        let market = Market {
-            total_borrow_assets,
-            total_borrow_shares,
-        };
-
-        let market_params = MarketParams {
-            loan_token,
-            collateral_token,
-            oracle: oracle_addr,
-            irm: Address::zero(),
-            lltv,
-        };
-
-        let oracle = IOracle::new(oracle_addr, self.client.clone());
-        let price = oracle.price().call().await?;
+        // Debt as of now, not as of the last interaction with the market
+        let MarketSnapshot { params: market_params, market, price } =
+            self.markets.snapshot(H256::from(market_id)).await?;
+        let loan_token = market_params.loan_token;
 
         let position = Position {
             borrow_shares,
             collateral,
         };
 
-        if position.is_healthy(&market, &market_params.lltv, &price) {
+        let debt_assets = to_assets_up(
+            U256::from(borrow_shares),
+            U256::from(market.total_borrow_assets),
+            U256::from(market.total_borrow_shares),
+        )?;
+        let collateral_value = mul_div_down(
+            U256::from(collateral),
+            price,
+            self.config.oracle_price_scale,
+        )?;
+
+        if position.is_healthy(&market, &market_params.lltv, &price)? {
             tracing::debug!("Borrower: {:?} is healthy in market: {:?}", borrower, H256::from(market_id));
-            return Ok(None);
+            self.classify(borrower, market_id, loan_token, PositionClass::Healthy, collateral_value, debt_assets).await;
+            return self.build_pre_liquidation(borrower, market_id, &market, &market_params, &position).await;
         }
 
+        let candidate = self.build_candidate(borrower, market_id, &market, &market_params, &position, price).await;
+
+        let profitable = matches!(candidate, Ok(Some(_)));
+        let class = PositionClass::liquidatable(profitable, collateral_value < debt_assets);
+        self.classify(borrower, market_id, loan_token, class, collateral_value, debt_assets).await;
+
+        candidate
+    }
+
+    async fn classify(
+        &self,
+        borrower: Address,
+        market_id: [u8; 32],
+        loan_token: Address,
+        class: PositionClass,
+        collateral_value: U256,
+        debt_value: U256,
+    ) {
+        let decimals = get_token_decimals(loan_token, self.client.clone()).await.unwrap_or(18);
+        let to_f64 = |amount: U256| {
+            format_units(amount, decimals as u32)
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or_default()
+        };
+
+        self.classifier.record(PositionSnapshot {
+            protocol: MORPHO,
+            market: H256::from(market_id),
+            borrower,
+            class,
+            collateral_value: to_f64(collateral_value),
+            debt_value: to_f64(debt_value),
+            value_unit: format!("{:?}", loan_token),
+        }).await;
+    }
+
+    /// Size and route the liquidation of an unhealthy position
+    async fn build_candidate(
+        &self,
+        borrower: Address,
+        market_id: [u8; 32],
+        market: &Market,
+        market_params: &MarketParams,
+        position: &Position,
+        price: U256,
+    ) -> anyhow::Result<Option<LiqCandidate>> {
+        let total_borrow_assets = market.total_borrow_assets;
+        let total_borrow_shares = market.total_borrow_shares;
+        let lltv = market_params.lltv;
+        let collateral = position.collateral;
+        let borrow_shares = position.borrow_shares;
+
         let debt_assets = to_assets_down(
             U256::from(borrow_shares),
             U256::from(total_borrow_assets),
             U256::from(total_borrow_shares),
-        );
+        )?;
 
         if debt_assets.is_zero() {
             return Ok(None);
         }
 
         // ─────────────────────────────────────────────
-        // 6. Partial liquidation sizing (50%)
+        // 6. Repay sizing: best net profit after the swap
         // ─────────────────────────────────────────────
-        let close_factor = U256::from_dec_str("850000000000000000")?;
-        let repay_assets = wmul_down(debt_assets, close_factor);
+        let terms = RepayTerms {
+            borrow_shares: U256::from(borrow_shares),
+            collateral: U256::from(collateral),
+            total_borrow_assets: U256::from(total_borrow_assets),
+            total_borrow_shares: U256::from(total_borrow_shares),
+            incentive_factor: incentive_factor(lltv)?,
+            price,
+            oracle_price_scale: self.config.oracle_price_scale,
+        };
 
-        // ─────────────────────────────────────────────
-        // 7. Incentive & theoretical seize
-        // ─────────────────────────────────────────────
-        let lif = incentive_factor(lltv);
-        let incentivized_repay = wmul_down(repay_assets, lif);
+        let swap_params = self.swap_params(market_params, U256::zero(), self.flash_liquidator.address()).await?;
+        let Some(size) = repay_optimizer::optimize(&ParaSwapClient::new(), &swap_params, &terms).await? else {
+            tracing::debug!("No profitable repay size for borrower {:?}", borrower);
+            return Ok(None);
+        };
 
-        let required_collateral = mul_div_down(
-            incentivized_repay,
-            self.config.oracle_price_scale,
-            price,
-        );
+        let min_profit = size.repay_assets * U256::from(self.config.chain.tuning().min_profit_bps) / U256::from(10_000u64);
+        if size.net_profit < min_profit {
+            tracing::debug!("Profit {} for borrower {:?} is below the minimum {}", size.net_profit, borrower, min_profit);
+            return Ok(None);
+        }
 
-        let available_collateral = U256::from(collateral);
+        self.route_candidate(borrower, market_id, market_params, size.repay_assets, size.mode, self.flash_liquidator.address()).await
+    }
 
-        // ─────────────────────────────────────────────
-        // 8. Decide liquidation mode (CRITICAL)
-        // ─────────────────────────────────────────────
-        let mode = if available_collateral >= required_collateral {
-            let repaid_shares = to_shares_down(
-                repay_assets,
-                U256::from(total_borrow_assets),
-                U256::from(total_borrow_shares),
-            );
-
-            if repaid_shares.is_zero() {
-                return Ok(None);
+    /// Pre-liquidate through a contract the borrower authorized, when the
+    /// position sits between its pre-LLTV and the market LLTV. The keeper
+    /// calls the contract itself, repaying from its own loan-token balance,
+    /// and sells the seized collateral afterwards: the deployed flash
+    /// liquidator has no pre-liquidation mode.
+    ///
+    /// `preLiquidate` accrues interest before pulling the repaid shares'
+    /// assets, so the keeper covers the snapshot amount plus a buffer.
+    async fn build_pre_liquidation(
+        &self,
+        borrower: Address,
+        market_id: [u8; 32],
+        market: &Market,
+        market_params: &MarketParams,
+        position: &Position,
+    ) -> anyhow::Result<Option<LiqCandidate>> {
+        for (contract, info) in self.pre_liquidations.for_position(borrower, H256::from(market_id)) {
+            let price = self.markets.price(info.oracle).await?;
+
+            let Some(size) = info.size(market_params.lltv, position, market, price)? else {
+                continue;
+            };
+
+            tracing::info!("🪝 Borrower {:?} is pre-liquidatable through {:?}", borrower, contract);
+
+            let max_repaid = size.repaid_assets
+                + size.repaid_assets * U256::from(PRE_LIQUIDATION_REPAY_BUFFER_BPS) / U256::from(10_000u64)
+                + U256::one();
+
+            let balance = IERC20::new(market_params.loan_token, self.client.clone())
+                .balance_of(self.config.keeper_address)
+                .call()
+                .await?;
+            if balance < max_repaid {
+                tracing::debug!(
+                    "Keeper holds {} of {:?}, {} needed to pre-liquidate {:?}",
+                    balance,
+                    market_params.loan_token,
+                    max_repaid,
+                    borrower
+                );
+                continue;
             }
 
-            LiquidationMode::RepayShares {
-                repaid_shares,
-                expected_seized_assets: required_collateral,
-            }
-        } else {
-            LiquidationMode::SeizeCollateral {
-                seized_assets: available_collateral,
-            }
-        };
+            let mode = LiquidationMode::RepayShares {
+                repaid_shares: size.repaid_shares,
+                expected_seized_assets: size.seized_assets,
+            };
+            let candidate = self
+                .route_candidate(borrower, market_id, market_params, max_repaid, mode, self.config.keeper_address)
+                .await?;
+
+            return Ok(candidate.map(|c| LiqCandidate { pre_liquidation: Some(contract), ..c }));
+        }
+
+        Ok(None)
+    }
+
+    /// Sale of `amount` collateral into the loan token by `seller`
+    async fn swap_params(&self, market_params: &MarketParams, amount: U256, seller: Address) -> anyhow::Result<SwapQueryParams> {
+        let (src_decimals, dest_decimals) = tokio::try_join!(
+            get_token_decimals(market_params.collateral_token, self.client.clone()),
+            get_token_decimals(market_params.loan_token, self.client.clone())
+        )?;
+
+        Ok(SwapQueryParams {
+            src_token: market_params.collateral_token.to_string(),
+            dest_token: market_params.loan_token.to_string(),
+            src_decimals,
+            dest_decimals,
+            amount: amount.to_string(),
+            side: "SELL".to_string(),
+            chain_id: self.config.chain.chain_id,
+            user_address: seller.to_string(),
+            slippage_bps: self.config.chain.tuning().morpho_slippage_bps,
+            receiver: seller.to_string()
+        })
+    }
+
+    /// Quote the collateral sale and assemble the candidate
+    async fn route_candidate(
+        &self,
+        borrower: Address,
+        market_id: [u8; 32],
+        market_params: &MarketParams,
+        repay_assets: U256,
+        mode: LiquidationMode,
+        seller: Address,
+    ) -> anyhow::Result<Option<LiqCandidate>> {
+        let loan_token = market_params.loan_token;
+        let collateral_token = market_params.collateral_token;
 
         // ─────────────────────────────────────────────
-        // 9. Swap sizing (economic only)
+        // 7. Swap sizing (economic only)
         // ─────────────────────────────────────────────
-        let collateral_for_swap = match &mode {
-            LiquidationMode::RepayShares {
-                expected_seized_assets,
-                ..
-            } => *expected_seized_assets,
-            LiquidationMode::SeizeCollateral { seized_assets } => *seized_assets,
-        };
+        let collateral_for_swap = mode.collateral_out();
 
         if collateral_for_swap.is_zero() {
             return Ok(None);
@@ -211,24 +408,7 @@ async fn analyze_borrower(
         // ─────────────────────────────────────────────
         //  ParaSwap routing
         // ─────────────────────────────────────────────
-        let (src_decimals, dest_decimals) = tokio::try_join!(
-            get_token_decimals(collateral_token, self.client.clone()),
-            get_token_decimals(loan_token, self.client.clone())
-        )?;
-
-        let swap_params = SwapQueryParams {
-            src_token: collateral_token.to_string(),
-            dest_token: loan_token.to_string(),
-            src_decimals: src_decimals,
-            dest_decimals: dest_decimals,
-            amount: collateral_for_swap.to_string(),
-            side: "SELL".to_string(),
-            chain_id: self.config.chain_id,
-            user_address: self.flash_liquidator.address().to_string(),
-            slippage_bps: 30,
-            receiver: self.flash_liquidator.address().to_string()
-
-        };
+        let swap_params = self.swap_params(market_params, collateral_for_swap, seller).await?;
 
         let paraswap_client = ParaSwapClient::new();
         let route = paraswap_client.compose_swap_data(swap_params).await?;
@@ -269,7 +449,9 @@ async fn analyze_borrower(
             swap_target: route.swap_target,
             swap_data: route.swap_data,
             swap_proxy: route.token_transfer_proxy,
-            min_amt_out: route.min_amt_out
+            swap_amount: route.src_amount,
+            min_amt_out: route.min_amt_out,
+            pre_liquidation: None,
         }))
     }
 }
@@ -281,13 +463,66 @@ where
 {
     async fn run(&self, block_number: u64) -> anyhow::Result<()> {
         tracing::info!("🚀 Running Morpho liquidation engine for block {}", block_number);
-        let candidates = self.generate_liquidations().await?;
+        let candidates = self.generate_liquidations(block_number).await?;
         if candidates.is_empty() {
             tracing::info!("Morpho Liquidator: No unhealthy borrowers to check");
             return Ok(());
         }
 
-        let jobs = candidates
+        self.execute_candidates(candidates, block_number).await?;
+        tracing::info!("Morpho liquidation cycle completed for block {}", block_number);
+        Ok(())
+    }
+
+    async fn price_feeds(&self) -> anyhow::Result<Vec<Address>> {
+        let markets: HashSet<H256> = self.watch_list
+            .snapshot()
+            .into_iter()
+            .map(|(_, market_id)| market_id)
+            .collect();
+
+        for market_id in markets {
+            if self.market_feeds.contains_key(&market_id) {
+                continue;
+            }
+
+            let oracle = self.markets.params(market_id).await?.oracle;
+            self.market_feeds.insert(market_id, self.oracle_feeds(oracle).await);
+        }
+
+        Ok(self.market_feeds.iter().flat_map(|entry| entry.value().clone()).collect())
+    }
+
+    async fn run_for_feeds(&self, block_number: u64, feeds: &[Address]) -> anyhow::Result<()> {
+        let candidates = self.generate_for_feeds(block_number, feeds).await?;
+        if !candidates.is_empty() {
+            self.execute_candidates(candidates, block_number).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<M: Middleware + 'static> MorphoLiquidator<M> {
+    async fn execute_candidates(
+        &self,
+        candidates: Vec<LiqCandidate>,
+        block_number: u64
+    ) -> anyhow::Result<()> {
+        let (pre_liquidations, flash): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|c| c.pre_liquidation.is_some());
+
+        for candidate in &pre_liquidations {
+            if let Err(e) = self.execute_pre_liquidation(candidate).await {
+                tracing::error!("Pre-liquidation of {:?} failed: {:?}", candidate.borrower, e);
+            }
+        }
+
+        if flash.is_empty() {
+            return Ok(());
+        }
+
+        let jobs = flash
             .into_iter()
             .map(|c| {
                 let debt = c.debt_to_cover;
@@ -296,7 +531,7 @@ where
             })
             .collect::<Vec<_>>();
 
-        let sim_sandbox: AnvilSandbox = create_simulation_sandbox(block_number, &self.flash_liquidator).await?;
+        let sim_sandbox: AnvilSandbox = create_simulation_sandbox(&self.config.chain, block_number, &self.flash_liquidator).await?;
         let snapshot_id = sim_sandbox.snapshot().await?;
 
         for (loan_amt, liq_params) in &jobs {
@@ -325,8 +560,67 @@ where
             }      
 
         }
-        tracing::info!("Morpho liquidation cycle completed for block {}", block_number);
         Ok(())
     }
-}
 
+    /// `preLiquidate` sent by the keeper, checked with an `eth_call` first,
+    /// then the seized collateral is sold along the candidate's route
+    async fn execute_pre_liquidation(&self, candidate: &LiqCandidate) -> anyhow::Result<()> {
+        let Some(contract) = candidate.pre_liquidation else {
+            return Ok(());
+        };
+        let keeper = self.config.keeper_address;
+
+        // `debt_to_cover` already carries the accrual buffer
+        let loan_token = IERC20::new(candidate.debt_token, self.client.clone());
+        if loan_token.allowance(keeper, contract).call().await? < candidate.debt_to_cover {
+            let receipt = loan_token.approve(contract, candidate.debt_to_cover).send().await?.await?;
+            ensure_mined(receipt, "loan token approval")?;
+        }
+
+        let call = IPreLiquidation::new(contract, self.client.clone()).pre_liquidate(
+            candidate.borrower,
+            U256::zero(),
+            candidate.repaid_shares,
+            Bytes::default(),
+        );
+        call.call()
+            .await
+            .map_err(|e| anyhow::anyhow!("Simulation Reverted: {:?}", e))?;
+
+        let receipt = ensure_mined(call.send().await?.await?, "preLiquidate")?;
+        tracing::info!(
+            "🪝 Pre-liquidated {:?} through {:?} (tx {:?})",
+            candidate.borrower,
+            contract,
+            receipt.transaction_hash
+        );
+
+        let receipt = IERC20::new(candidate.collateral_token, self.client.clone())
+            .approve(candidate.swap_proxy, candidate.swap_amount)
+            .send()
+            .await?
+            .await?;
+        ensure_mined(receipt, "collateral approval")?;
+
+        let swap_tx = TransactionRequest::new()
+            .from(keeper)
+            .to(candidate.swap_target)
+            .data(candidate.swap_data.clone());
+        let receipt = self.client
+            .send_transaction(swap_tx, None)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send swap tx: {:?}", e))?
+            .await?;
+        let receipt = ensure_mined(receipt, "pre-liquidation swap")?;
+
+        tracing::info!(
+            "💱 Sold {} of {:?} seized by pre-liquidation (tx {:?})",
+            candidate.swap_amount,
+            candidate.collateral_token,
+            receipt.transaction_hash
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use ethers::types::U256;
+use anyhow::{anyhow, ensure, Result};
+use ethers::types::{U256, U512};
 use crate::constants::{
     WAD,
     VIRTUAL_ASSETS,
@@ -7,6 +8,11 @@ use crate::constants::{
     LIQUIDATION_CURSOR,
 };
 
+// Mirrors Morpho Blue's `MathLib` and `SharesMathLib`. Every function is
+// checked: an odd oracle price or an empty market is an error for that
+// borrower, never a panic. Products are formed in U512, so only a result
+// that does not fit in 256 bits fails.
+
 #[inline]
 pub fn min(a: U256, b: U256) -> U256 {
     if a < b { a } else { b }
@@ -18,70 +24,204 @@ pub fn max(a: U256, b: U256) -> U256 {
 }
 
 #[inline]
-pub fn mul_div_down(x: U256, y: U256, d: U256) -> U256 {
-    x.checked_mul(y)
-        .expect("mul overflow")
-        .checked_div(d)
-        .expect("div by zero")
+fn narrow(x: U512) -> Result<U256> {
+    U256::try_from(x).map_err(|_| anyhow!("math overflow"))
+}
+
+#[inline]
+pub fn checked_add(a: U256, b: U256) -> Result<U256> {
+    a.checked_add(b).ok_or_else(|| anyhow!("add overflow"))
+}
+
+#[inline]
+pub fn checked_sub(a: U256, b: U256) -> Result<U256> {
+    a.checked_sub(b).ok_or_else(|| anyhow!("sub underflow"))
+}
+
+/// `(x * y) / d` rounded down
+#[inline]
+pub fn mul_div_down(x: U256, y: U256, d: U256) -> Result<U256> {
+    ensure!(!d.is_zero(), "div by zero");
+    narrow(x.full_mul(y) / U512::from(d))
 }
 
+/// `(x * y) / d` rounded up
 #[inline]
-pub fn mul_div_up(x: U256, y: U256, d: U256) -> U256 {
-    let n = x.checked_mul(y).expect("mul overflow");
-    (n + d - U256::one())
-        .checked_div(d)
-        .expect("div by zero")
+pub fn mul_div_up(x: U256, y: U256, d: U256) -> Result<U256> {
+    ensure!(!d.is_zero(), "div by zero");
+    let d = U512::from(d);
+    narrow((x.full_mul(y) + d - U512::one()) / d)
 }
 
 #[inline]
-pub fn wmul_down(x: U256, y: U256) -> U256 {
+pub fn wmul_down(x: U256, y: U256) -> Result<U256> {
     mul_div_down(x, y, *WAD)
 }
 
 #[inline]
-pub fn wdiv_down(x: U256, y: U256) -> U256 {
+pub fn wdiv_down(x: U256, y: U256) -> Result<U256> {
     mul_div_down(x, *WAD, y)
 }
 
 #[inline]
-pub fn wdiv_up(x: U256, y: U256) -> U256 {
+pub fn wdiv_up(x: U256, y: U256) -> Result<U256> {
     mul_div_up(x, *WAD, y)
 }
 
 #[inline]
-pub fn to_assets_down(shares: U256, total_assets: U256, total_shares: U256) -> U256 {
+pub fn to_assets_down(shares: U256, total_assets: U256, total_shares: U256) -> Result<U256> {
     mul_div_down(
         shares,
-        total_assets + U256::from(VIRTUAL_ASSETS),
-        total_shares + U256::from(VIRTUAL_SHARES),
+        checked_add(total_assets, U256::from(VIRTUAL_ASSETS))?,
+        checked_add(total_shares, U256::from(VIRTUAL_SHARES))?,
     )
 }
 
 #[inline]
-pub fn to_assets_up(shares: U256, total_assets: U256, total_shares: U256) -> U256 {
+pub fn to_assets_up(shares: U256, total_assets: U256, total_shares: U256) -> Result<U256> {
     mul_div_up(
         shares,
-        total_assets + U256::from(VIRTUAL_ASSETS),
-        total_shares + U256::from(VIRTUAL_SHARES),
+        checked_add(total_assets, U256::from(VIRTUAL_ASSETS))?,
+        checked_add(total_shares, U256::from(VIRTUAL_SHARES))?,
     )
 }
 
 #[inline]
-pub fn to_shares_down(assets: U256, total_assets: U256, total_shares: U256) -> U256 {
+pub fn to_shares_down(assets: U256, total_assets: U256, total_shares: U256) -> Result<U256> {
     mul_div_down(
         assets,
-        total_shares + U256::from(VIRTUAL_SHARES),
-        total_assets + U256::from(VIRTUAL_ASSETS),
+        checked_add(total_shares, U256::from(VIRTUAL_SHARES))?,
+        checked_add(total_assets, U256::from(VIRTUAL_ASSETS))?,
     )
 }
 
 #[inline]
-pub fn incentive_factor(lltv: U256) -> U256 {
-    min(
-        *MAX_LIQUIDATION_INCENTIVE_FACTOR,
-        wdiv_down(
-            *WAD,
-            *WAD - wmul_down(*LIQUIDATION_CURSOR, *WAD - lltv),
-        ),
+pub fn to_shares_up(assets: U256, total_assets: U256, total_shares: U256) -> Result<U256> {
+    mul_div_up(
+        assets,
+        checked_add(total_shares, U256::from(VIRTUAL_SHARES))?,
+        checked_add(total_assets, U256::from(VIRTUAL_ASSETS))?,
     )
 }
+
+/// `e^(x * n) - 1` to the third order, as `MathLib.wTaylorCompounded`
+#[inline]
+pub fn w_taylor_compounded(x: U256, n: U256) -> Result<U256> {
+    let first_term = narrow(x.full_mul(n))?;
+    let second_term = mul_div_down(first_term, first_term, *WAD * 2)?;
+    let third_term = mul_div_down(second_term, first_term, *WAD * 3)?;
+
+    checked_add(checked_add(first_term, second_term)?, third_term)
+}
+
+/// Borrow interest accrued over `elapsed` seconds at `borrow_rate` per second
+#[inline]
+pub fn accrued_interest(total_borrow_assets: U256, borrow_rate: U256, elapsed: u64) -> Result<U256> {
+    wmul_down(total_borrow_assets, w_taylor_compounded(borrow_rate, U256::from(elapsed))?)
+}
+
+#[inline]
+pub fn incentive_factor(lltv: U256) -> Result<U256> {
+    let cursor_discount = wmul_down(*LIQUIDATION_CURSOR, checked_sub(*WAD, lltv)?)?;
+
+    Ok(min(
+        *MAX_LIQUIDATION_INCENTIVE_FACTOR,
+        wdiv_down(*WAD, checked_sub(*WAD, cursor_discount)?)?,
+    ))
+}
+
+// Expected values follow the integer semantics of `MathLib` and
+// `SharesMathLib` (VIRTUAL_SHARES = 1e6, VIRTUAL_ASSETS = 1,
+// LIQUIDATION_CURSOR = 0.3e18, MAX_LIQUIDATION_INCENTIVE_FACTOR = 1.15e18).
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(s: &str) -> U256 {
+        U256::from_dec_str(s).unwrap()
+    }
+
+    #[test]
+    fn mul_div_rounds_in_the_requested_direction() {
+        assert_eq!(mul_div_down(10.into(), 3.into(), 4.into()).unwrap(), 7.into());
+        assert_eq!(mul_div_up(10.into(), 3.into(), 4.into()).unwrap(), 8.into());
+        // Exact division does not round up
+        assert_eq!(mul_div_up(12.into(), 1.into(), 4.into()).unwrap(), 3.into());
+        assert_eq!(mul_div_up(0.into(), 5.into(), 7.into()).unwrap(), 0.into());
+    }
+
+    #[test]
+    fn mul_div_keeps_wide_intermediates() {
+        assert_eq!(mul_div_down(U256::MAX, U256::MAX, U256::MAX).unwrap(), U256::MAX);
+        assert_eq!(mul_div_up(U256::MAX, U256::MAX, U256::MAX).unwrap(), U256::MAX);
+    }
+
+    #[test]
+    fn mul_div_fails_instead_of_panicking() {
+        assert!(mul_div_down(U256::MAX, 2.into(), 1.into()).is_err());
+        assert!(mul_div_up(U256::MAX, 2.into(), 1.into()).is_err());
+        assert!(mul_div_down(1.into(), 1.into(), U256::zero()).is_err());
+        assert!(mul_div_up(1.into(), 1.into(), U256::zero()).is_err());
+        assert!(wdiv_down(1.into(), U256::zero()).is_err());
+    }
+
+    #[test]
+    fn shares_math_on_an_empty_market_uses_virtual_shares() {
+        // 1 asset is worth 1e6 shares in an empty market
+        assert_eq!(to_shares_down(1.into(), 0.into(), 0.into()).unwrap(), 1_000_000.into());
+        assert_eq!(to_assets_down(1_000.into(), 0.into(), 0.into()).unwrap(), 0.into());
+        assert_eq!(to_assets_up(1_000.into(), 0.into(), 0.into()).unwrap(), 1.into());
+    }
+
+    #[test]
+    fn shares_math_rounding() {
+        let total_assets = u("1000000000000");
+        let total_shares = u("987654321000000000");
+        assert_eq!(to_assets_down(123_456_789.into(), total_assets, total_shares).unwrap(), 124.into());
+        assert_eq!(to_assets_up(123_456_789.into(), total_assets, total_shares).unwrap(), 125.into());
+
+        assert_eq!(to_shares_down(10.into(), 2.into(), 0.into()).unwrap(), 3_333_333.into());
+        assert_eq!(to_shares_up(10.into(), 2.into(), 0.into()).unwrap(), 3_333_334.into());
+    }
+
+    #[test]
+    fn incentive_factor_vectors() {
+        let cases = [
+            ("0", "1150000000000000000"),
+            ("385000000000000000", "1150000000000000000"),
+            ("625000000000000000", "1126760563380281690"),
+            ("770000000000000000", "1074113856068743286"),
+            ("860000000000000000", "1043841336116910229"),
+            ("915000000000000000", "1026167265264238070"),
+            ("945000000000000000", "1016776817488561260"),
+            ("965000000000000000", "1010611419909044972"),
+            ("980000000000000000", "1006036217303822937"),
+            ("1000000000000000000", "1000000000000000000"),
+        ];
+
+        for (lltv, expected) in cases {
+            assert_eq!(incentive_factor(u(lltv)).unwrap(), u(expected), "lltv {}", lltv);
+        }
+    }
+
+    #[test]
+    fn incentive_factor_rejects_lltv_above_one() {
+        assert!(incentive_factor(u("1000000000000000001")).is_err());
+    }
+
+    #[test]
+    fn taylor_compounding_vectors() {
+        // ~5% APR per second
+        let rate = u("1585489599");
+
+        assert_eq!(w_taylor_compounded(rate, 0.into()).unwrap(), 0.into());
+        assert_eq!(w_taylor_compounded(rate, 1.into()).unwrap(), u("1585489600"));
+        assert_eq!(w_taylor_compounded(rate, 31_536_000.into()).unwrap(), u("51270833327093113"));
+        assert_eq!(accrued_interest(u("1000000000000"), rate, 86_400).unwrap(), u("136995684"));
+    }
+
+    #[test]
+    fn taylor_compounding_overflow_is_an_error() {
+        assert!(w_taylor_compounded(U256::MAX, 2.into()).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use ethers::providers::Middleware;
+use sled::Db;
+use tokio::sync::mpsc;
+
+use crate::{
+    bootstrap_engine::{
+        bootstrap_state::BootstrapState,
+        morpho_bootstrap::MorphoBootstrap,
+        pre_liquidation_bootstrap::PreLiquidationBootstrap,
+        Bootstrap,
+    },
+    chain_profile::ChainProfile,
+    common::plugin::{Engine, EngineContext, ProtocolPlugin},
+};
+
+use super::{
+    abi_bindings::{IMorphoBlue, IPreLiquidationFactory},
+    MorphoInstance,
+};
+
+/// Morpho Blue, with pre-liquidations when a factory is configured
+pub struct MorphoPlugin<M> {
+    client: Arc<M>,
+    instance: MorphoInstance,
+}
+
+impl<M: Middleware + 'static> MorphoPlugin<M> {
+    pub fn load(chain: Arc<ChainProfile>, client: Arc<M>, db: Arc<Db>) -> anyhow::Result<Self> {
+        let instance = super::load_instance(chain, db)?;
+        Ok(Self { client, instance })
+    }
+
+    fn morpho(&self) -> IMorphoBlue<M> {
+        IMorphoBlue::new(self.instance.config.morpho_blue, self.client.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> ProtocolPlugin for MorphoPlugin<M> {
+    fn name(&self) -> &'static str {
+        "morpho"
+    }
+
+    fn bootstraps(&self, state: Arc<BootstrapState>) -> Vec<Arc<dyn Bootstrap>> {
+        let mut bootstraps: Vec<Arc<dyn Bootstrap>> = vec![Arc::new(MorphoBootstrap::new(
+            self.morpho(),
+            self.instance.watch_list.clone(),
+            self.instance.markets.clone(),
+            state.clone(),
+            self.client.clone(),
+            self.instance.config.deploy_block,
+        ))];
+
+        if let Some(factory) = self.instance.config.pre_liquidation_factory {
+            bootstraps.push(Arc::new(PreLiquidationBootstrap::new(
+                IPreLiquidationFactory::new(factory, self.client.clone()),
+                self.morpho(),
+                self.instance.pre_liquidations.clone(),
+                state,
+                self.client.clone(),
+                self.instance.config.pre_liquidation_factory_block,
+            )));
+        }
+
+        bootstraps
+    }
+
+    async fn start(self: Box<Self>, ctx: EngineContext) -> anyhow::Result<Vec<Engine>> {
+        let name = self.name().to_string();
+        let (admin_tx, admin_rx) = mpsc::channel(64);
+        let liquidator = super::start_engine(
+            self.client,
+            ctx.shutdown_rx,
+            admin_rx,
+            self.instance,
+            ctx.classifier,
+        ).await?;
+
+        Ok(vec![Engine { name, liquidator, admin_tx }])
+    }
+}
@@ -0,0 +1,277 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use ethers::{
+    providers::Middleware,
+    types::{Address, H256, U256},
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sled::{Db, Tree};
+use tokio::sync::watch;
+
+use super::{
+    abi_bindings::{CreatePreLiquidationFilter, IMorphoBlue, IPreLiquidationFactory, SetAuthorizationFilter},
+    morpho_math::*,
+    types::{Market, Position},
+};
+
+use crate::constants::{ORACLE_PRICE_SCALE, WAD};
+
+/// Parameters of a pre-liquidation contract, fixed at creation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PreLiquidationInfo {
+    pub market_id: H256,
+    pub pre_lltv: U256,
+    pub pre_lcf_1: U256,
+    pub pre_lcf_2: U256,
+    pub pre_lif_1: U256,
+    pub pre_lif_2: U256,
+    pub oracle: Address,
+}
+
+pub struct PreLiquidationSize {
+    pub repaid_shares: U256,
+    pub repaid_assets: U256,
+    pub seized_assets: U256,
+}
+
+impl From<CreatePreLiquidationFilter> for PreLiquidationInfo {
+    fn from(ev: CreatePreLiquidationFilter) -> Self {
+        let (pre_lltv, pre_lcf_1, pre_lcf_2, pre_lif_1, pre_lif_2, oracle) = ev.pre_liquidation_params;
+        Self {
+            market_id: H256::from(ev.id),
+            pre_lltv,
+            pre_lcf_1,
+            pre_lcf_2,
+            pre_lif_1,
+            pre_lif_2,
+            oracle,
+        }
+    }
+}
+
+impl PreLiquidationInfo {
+    /// Largest pre-liquidation the contract accepts, mirroring
+    /// `PreLiquidation.preLiquidate` with `seizedAssets = 0`.
+    ///
+    /// `None` when the LTV is not strictly above pre-LLTV, or already
+    /// above LLTV where a regular liquidation applies.
+    pub fn size(&self, lltv: U256, position: &Position, market: &Market, price: U256) -> Result<Option<PreLiquidationSize>> {
+        let total_assets = U256::from(market.total_borrow_assets);
+        let total_shares = U256::from(market.total_borrow_shares);
+        let borrow_shares = U256::from(position.borrow_shares);
+
+        let collateral_quoted = mul_div_down(U256::from(position.collateral), price, *ORACLE_PRICE_SCALE)?;
+        if collateral_quoted.is_zero() || lltv <= self.pre_lltv {
+            return Ok(None);
+        }
+
+        let borrowed = to_assets_up(borrow_shares, total_assets, total_shares)?;
+        let ltv = wdiv_up(borrowed, collateral_quoted)?;
+        if ltv <= self.pre_lltv || ltv > lltv {
+            return Ok(None);
+        }
+
+        // Close factor and incentive interpolate linearly from pre-LLTV to LLTV
+        let quotient = wdiv_down(ltv - self.pre_lltv, lltv - self.pre_lltv)?;
+        let pre_lif = checked_add(wmul_down(quotient, checked_sub(self.pre_lif_2, self.pre_lif_1)?)?, self.pre_lif_1)?;
+        let pre_lcf = checked_add(wmul_down(quotient, checked_sub(self.pre_lcf_2, self.pre_lcf_1)?)?, self.pre_lcf_1)?;
+
+        let repaid_shares = wmul_down(borrow_shares, min(pre_lcf, *WAD))?;
+        if repaid_shares.is_zero() {
+            return Ok(None);
+        }
+
+        let seized_assets = mul_div_down(
+            wmul_down(to_assets_down(repaid_shares, total_assets, total_shares)?, pre_lif)?,
+            *ORACLE_PRICE_SCALE,
+            price,
+        )?;
+        if seized_assets.is_zero() || seized_assets > U256::from(position.collateral) {
+            return Ok(None);
+        }
+
+        Ok(Some(PreLiquidationSize {
+            repaid_shares,
+            repaid_assets: to_assets_up(repaid_shares, total_assets, total_shares)?,
+            seized_assets,
+        }))
+    }
+}
+
+/// Morpho pre-liquidation contracts and the borrowers that authorized them.
+///
+/// Maintains:
+///     pre-liquidation contract → PreLiquidationInfo   ("morpho:pre_liquidations")
+///     borrower ++ contract → ()                        ("morpho:pre_liquidation_auths")
+pub struct PreLiquidationRegistry {
+    contracts_db: Arc<Tree>,
+    auth_db: Arc<Tree>,
+    contracts: DashMap<Address, PreLiquidationInfo>,
+    /// borrower → authorized pre-liquidation contracts
+    authorizations: DashMap<Address, HashSet<Address>>,
+}
+
+impl PreLiquidationRegistry {
+    pub fn new(db: Arc<Db>) -> Result<Self> {
+        let contracts_tree = db.open_tree("morpho:pre_liquidations")?;
+        let auth_tree = db.open_tree("morpho:pre_liquidation_auths")?;
+
+        let contracts = DashMap::new();
+        for item in contracts_tree.iter() {
+            let (k, v) = item?;
+            contracts.insert(Address::from_slice(&k), bincode::deserialize(&v)?);
+        }
+
+        let authorizations: DashMap<Address, HashSet<Address>> = DashMap::new();
+        for item in auth_tree.iter() {
+            let (k, _) = item?;
+            authorizations
+                .entry(Address::from_slice(&k[..20]))
+                .or_default()
+                .insert(Address::from_slice(&k[20..]));
+        }
+
+        Ok(Self {
+            contracts_db: Arc::new(contracts_tree),
+            auth_db: Arc::new(auth_tree),
+            contracts,
+            authorizations,
+        })
+    }
+
+    pub async fn register(&self, contract: Address, info: PreLiquidationInfo) -> Result<()> {
+        if self.contracts.insert(contract, info).is_some() {
+            return Ok(());
+        }
+
+        tracing::info!("🪝 Tracking Morpho pre-liquidation {:?} (market {:?})", contract, info.market_id);
+
+        let db = self.contracts_db.clone();
+        let encoded = bincode::serialize(&info)?;
+        tokio::task::spawn_blocking(move || db.insert(contract.as_bytes(), encoded)).await??;
+
+        Ok(())
+    }
+
+    /// Apply a `SetAuthorization`, ignoring authorizations of other contracts
+    pub async fn set_authorization(&self, borrower: Address, contract: Address, authorized: bool) -> Result<()> {
+        if !self.contracts.contains_key(&contract) {
+            return Ok(());
+        }
+
+        if authorized {
+            self.authorizations.entry(borrower).or_default().insert(contract);
+        } else if let Some(mut contracts) = self.authorizations.get_mut(&borrower) {
+            contracts.remove(&contract);
+        }
+
+        let db = self.auth_db.clone();
+        let key = [borrower.as_bytes(), contract.as_bytes()].concat();
+        tokio::task::spawn_blocking(move || {
+            if authorized {
+                db.insert(key, &[])?;
+            } else {
+                db.remove(key)?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Pre-liquidation contracts of `market_id` the borrower authorized
+    pub fn for_position(&self, borrower: Address, market_id: H256) -> Vec<(Address, PreLiquidationInfo)> {
+        let Some(contracts) = self.authorizations.get(&borrower) else {
+            return vec![];
+        };
+
+        contracts
+            .iter()
+            .filter_map(|contract| self.contracts.get(contract).map(|info| (*contract, *info)))
+            .filter(|(_, info)| info.market_id == market_id)
+            .collect()
+    }
+
+    pub async fn on_created(&self, ev: CreatePreLiquidationFilter) -> Result<()> {
+        self.register(ev.pre_liquidation, PreLiquidationInfo::from(ev)).await
+    }
+
+    pub async fn on_authorization(&self, ev: SetAuthorizationFilter) -> Result<()> {
+        self.set_authorization(ev.authorizer, ev.authorized, ev.new_is_authorized).await
+    }
+}
+
+/// Follows new pre-liquidation contracts and authorization changes
+pub struct PreLiquidationIndexer<M: Middleware + 'static> {
+    registry: Arc<PreLiquidationRegistry>,
+    factory: IPreLiquidationFactory<M>,
+    morpho: IMorphoBlue<M>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl<M: Middleware + 'static> PreLiquidationIndexer<M> {
+    pub fn new(
+        registry: Arc<PreLiquidationRegistry>,
+        factory: IPreLiquidationFactory<M>,
+        morpho: IMorphoBlue<M>,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            registry,
+            factory,
+            morpho,
+            shutdown,
+        }
+    }
+
+    pub async fn start(mut self) -> Result<()> {
+        tracing::info!("🪝 Morpho pre-liquidation indexer started");
+
+        let create_filter = self.factory.create_pre_liquidation_filter();
+        let auth_filter = self.morpho.set_authorization_filter();
+
+        let mut create_stream = create_filter.stream().await?;
+        let mut auth_stream = auth_filter.stream().await?;
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.changed() => {
+                    tracing::info!("🛑 Morpho pre-liquidation indexer shutting down");
+                    break;
+                }
+
+                evt = create_stream.next() => {
+                    match evt {
+                        Some(Ok(ev)) => self.registry.on_created(ev).await?,
+                        Some(Err(e)) => {
+                            tracing::error!("CreatePreLiquidation stream error: {:?}", e);
+                        }
+                        None => {
+                            tracing::warn!("CreatePreLiquidation stream ended");
+                            break;
+                        }
+                    }
+                }
+
+                evt = auth_stream.next() => {
+                    match evt {
+                        Some(Ok(ev)) => self.registry.on_authorization(ev).await?,
+                        Some(Err(e)) => {
+                            tracing::error!("SetAuthorization stream error: {:?}", e);
+                        }
+                        None => {
+                            tracing::warn!("SetAuthorization stream ended");
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
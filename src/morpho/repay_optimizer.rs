@@ -0,0 +1,184 @@
+use ethers::types::U256;
+
+use super::{morpho_math::*, types::LiquidationMode};
+
+use crate::common::{paraswap::ParaSwapClient, SwapQueryParams};
+
+/// Repay sizes probed on the first pass, in bps of the largest repay
+const GRID_BPS: [u64; 5] = [2_000, 4_000, 6_000, 8_000, 10_000];
+
+/// Bisection steps around the best grid point
+const REFINE_STEPS: usize = 2;
+
+/// Market state a liquidation is sized against
+pub struct RepayTerms {
+    pub borrow_shares: U256,
+    pub collateral: U256,
+    pub total_borrow_assets: U256,
+    pub total_borrow_shares: U256,
+    pub incentive_factor: U256,
+    pub price: U256,
+    pub oracle_price_scale: U256,
+}
+
+pub struct RepaySize {
+    pub mode: LiquidationMode,
+    /// Loan assets pulled by Morpho, the flash loan amount
+    pub repay_assets: U256,
+    /// Expected swap output minus `repay_assets`
+    pub net_profit: U256,
+}
+
+impl RepayTerms {
+    fn debt_assets(&self) -> anyhow::Result<U256> {
+        to_assets_up(self.borrow_shares, self.total_borrow_assets, self.total_borrow_shares)
+    }
+
+    /// Repay that seizes all collateral, the upper bound on any repay
+    fn max_repay(&self) -> anyhow::Result<U256> {
+        let collateral_quoted = mul_div_down(self.collateral, self.price, self.oracle_price_scale)?;
+        Ok(min(self.debt_assets()?, wdiv_down(collateral_quoted, self.incentive_factor)?))
+    }
+
+    /// What Morpho's `liquidate` does for a target repay: repay shares
+    /// when the collateral covers it, otherwise seize all collateral.
+    /// Exactly one of shares or seized assets is set either way.
+    fn size(&self, target: U256) -> anyhow::Result<Option<(LiquidationMode, U256)>> {
+        let (total_assets, total_shares) = (self.total_borrow_assets, self.total_borrow_shares);
+
+        let repaid_shares = min(to_shares_down(target, total_assets, total_shares)?, self.borrow_shares);
+        let seized = mul_div_down(
+            wmul_down(to_assets_down(repaid_shares, total_assets, total_shares)?, self.incentive_factor)?,
+            self.oracle_price_scale,
+            self.price,
+        )?;
+
+        if !repaid_shares.is_zero() && seized <= self.collateral {
+            let mode = LiquidationMode::RepayShares {
+                repaid_shares,
+                expected_seized_assets: seized,
+            };
+            return Ok(Some((mode, to_assets_up(repaid_shares, total_assets, total_shares)?)));
+        }
+
+        if self.collateral.is_zero() {
+            return Ok(None);
+        }
+
+        let repaid_shares = to_shares_up(
+            wdiv_up(mul_div_up(self.collateral, self.price, self.oracle_price_scale)?, self.incentive_factor)?,
+            total_assets,
+            total_shares,
+        )?;
+        let mode = LiquidationMode::SeizeCollateral {
+            seized_assets: self.collateral,
+        };
+        Ok(Some((mode, to_assets_up(repaid_shares, total_assets, total_shares)?)))
+    }
+}
+
+/// Search the repay size with the highest net profit after the swap.
+///
+/// Price impact makes profit concave in the repay, so a coarse grid over
+/// `(0, max_repay]` is refined by bisecting towards the better neighbour
+/// of the best point. `None` when no probed size is profitable.
+pub async fn optimize(
+    paraswap: &ParaSwapClient,
+    swap_params: &SwapQueryParams,
+    terms: &RepayTerms,
+) -> anyhow::Result<Option<RepaySize>> {
+    let max_repay = terms.max_repay()?;
+    if max_repay.is_zero() {
+        return Ok(None);
+    }
+
+    let at_bps = |bps: u64| max_repay * U256::from(bps) / U256::from(10_000u64);
+
+    let mut probed: Vec<(u64, Option<RepaySize>)> = Vec::with_capacity(GRID_BPS.len() + REFINE_STEPS * 2);
+    for bps in GRID_BPS {
+        probed.push((bps, probe(paraswap, swap_params, terms, at_bps(bps)).await));
+    }
+
+    let mut best_bps = best_of(&probed);
+    let mut step = GRID_BPS[0] / 2;
+
+    for _ in 0..REFINE_STEPS {
+        let Some(center) = best_bps else { break };
+
+        for bps in [center.saturating_sub(step), (center + step).min(10_000)] {
+            if bps == 0 || probed.iter().any(|(b, _)| *b == bps) {
+                continue;
+            }
+            probed.push((bps, probe(paraswap, swap_params, terms, at_bps(bps)).await));
+        }
+
+        best_bps = best_of(&probed);
+        step /= 2;
+    }
+
+    let best = best_bps.and_then(|bps| {
+        probed
+            .into_iter()
+            .find(|(b, _)| *b == bps)
+            .and_then(|(_, size)| size)
+    });
+
+    if let Some(size) = &best {
+        tracing::debug!(
+            "Morpho repay optimizer: repay {} of max {} for net profit {}",
+            size.repay_assets,
+            max_repay,
+            size.net_profit
+        );
+    }
+
+    Ok(best)
+}
+
+fn best_of(probed: &[(u64, Option<RepaySize>)]) -> Option<u64> {
+    probed
+        .iter()
+        .filter_map(|(bps, size)| size.as_ref().map(|s| (*bps, s.net_profit)))
+        .max_by_key(|(_, profit)| *profit)
+        .map(|(bps, _)| bps)
+}
+
+/// Quote one repay size; failed quotes and losses count as unprofitable
+async fn probe(
+    paraswap: &ParaSwapClient,
+    swap_params: &SwapQueryParams,
+    terms: &RepayTerms,
+    target: U256,
+) -> Option<RepaySize> {
+    let (mode, repay_assets) = match terms.size(target) {
+        Ok(size) => size?,
+        Err(e) => {
+            tracing::debug!("Repay probe sizing failed for {}: {:?}", target, e);
+            return None;
+        }
+    };
+
+    let seized = mode.collateral_out();
+    if seized.is_zero() {
+        return None;
+    }
+
+    let params = SwapQueryParams {
+        amount: seized.to_string(),
+        ..swap_params.clone()
+    };
+
+    let out = match paraswap.quote(&params).await {
+        Ok(out) => out,
+        Err(e) => {
+            tracing::debug!("Repay probe quote failed for {}: {:?}", seized, e);
+            return None;
+        }
+    };
+
+    (out > repay_assets).then(|| RepaySize {
+        mode,
+        repay_assets,
+        net_profit: out - repay_assets,
+    })
+}
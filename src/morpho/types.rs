@@ -1,9 +1,9 @@
 use ethers::types::{Address, U256, H256, Bytes};
-use crate::{constants::{ORACLE_PRICE_SCALE, WAD}};
-use super::morpho_math::to_assets_down;
+use crate::{common::abi_bindings::LiquidationParams, constants::ORACLE_PRICE_SCALE};
+use super::morpho_math::{mul_div_down, to_assets_up, wmul_down};
 
 pub trait HealthCheck {
-    fn is_healthy(&self, market: &Market, lltv: &U256, price: &U256) -> bool;
+    fn is_healthy(&self, market: &Market, lltv: &U256, price: &U256) -> anyhow::Result<bool>;
 }
 
 
@@ -21,6 +21,16 @@ pub enum LiquidationMode {
     },
 }
 
+impl LiquidationMode {
+    /// Collateral the liquidation hands to the liquidator
+    pub fn collateral_out(&self) -> U256 {
+        match self {
+            LiquidationMode::RepayShares { expected_seized_assets, .. } => *expected_seized_assets,
+            LiquidationMode::SeizeCollateral { seized_assets } => *seized_assets,
+        }
+    }
+}
+
 pub struct LiqCandidate{
     pub debt_to_cover: U256,
     pub borrower: Address,
@@ -32,13 +42,19 @@ pub struct LiqCandidate{
     pub swap_target: Address,
     pub swap_data: Bytes,
     pub swap_proxy: Address,
-    pub min_amt_out: U256
+    /// Collateral the swap sells
+    pub swap_amount: U256,
+    pub min_amt_out: U256,
+    /// Authorized pre-liquidation contract the keeper calls directly,
+    /// `None` for a flash liquidation
+    pub pre_liquidation: Option<Address>,
 }
 
+/// Borrow side of a market, with interest accrued to the latest block
+#[derive(Debug, Clone, Copy)]
 pub struct Market {
     pub total_borrow_assets: u128,
     pub total_borrow_shares: u128,
-   
 }
 
 pub struct Position {
@@ -47,29 +63,77 @@ pub struct Position {
 }
 
 impl HealthCheck for Position {
-    fn is_healthy(&self, market: &Market, lltv: &U256, price: &U256) -> bool {
+    fn is_healthy(&self, market: &Market, lltv: &U256, price: &U256) -> anyhow::Result<bool> {
         // collateral value in loan asset units
         // collateral * price / 1e36
-        let collateral_value = U256::from(self.collateral)
-            * *price
-            / *ORACLE_PRICE_SCALE;
+        let collateral_value = mul_div_down(U256::from(self.collateral), *price, *ORACLE_PRICE_SCALE)?;
 
         // max borrow = collateral_value * lltv / 1e18
-        let max_borrow = collateral_value
-            * *lltv
-            / *WAD;
+        let max_borrow = wmul_down(collateral_value, *lltv)?;
 
-        // borrowed assets = borrowShares * (totalBorrowAssets + virtual) / (totalBorrowShares + virtual)
-        let borrowed_assets = to_assets_down(
+        // borrowed assets = borrowShares * (totalBorrowAssets + virtual) / (totalBorrowShares + virtual),
+        // rounded up like Morpho's `_isHealthy`
+        let borrowed_assets = to_assets_up(
             U256::from(self.borrow_shares),
             U256::from(market.total_borrow_assets),
             U256::from(market.total_borrow_shares)
-        );
+        )?;
 
-        max_borrow >= borrowed_assets
+        Ok(max_borrow >= borrowed_assets)
     }
     
 }
 
 
 
+
+impl From<LiqCandidate> for LiquidationParams {
+    fn from(value: LiqCandidate) -> Self {
+        Self { 
+            mode: 1, 
+            borrower: value.borrower, 
+            aave_debt_asset: Address::zero(), 
+            aave_collateral: Address::zero(), 
+            aave_debt_to_cover: U256::zero(), 
+            morpho_market_id: value.market_id.to_fixed_bytes(), 
+            morpho_repaid_shares: value.repaid_shares, 
+            morpho_seized_assets: value.seized_assets,
+            compound_collateral: Address::zero(),
+            compound_debt_asset: Address::zero(),
+            compound_debt_to_cover: U256::zero(),
+            compound_min_collateral: U256::zero(),
+            swap_target: value.swap_target, 
+            swap_allowance_target: value.swap_proxy, 
+            swap_data: value.swap_data,
+            flash_asset: value.debt_token,
+            min_amt_out: value.min_amt_out 
+        }
+    }
+    
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One loan asset per 1e6 shares (virtual shares included), collateral
+    /// priced 1:1 and an LLTV of 100%, so one unit of collateral backs
+    /// exactly one unit of debt
+    fn at_boundary(borrow_shares: u128) -> bool {
+        let market = Market { total_borrow_assets: 1, total_borrow_shares: 1_000_000 };
+        let position = Position { borrow_shares, collateral: 1 };
+
+        position.is_healthy(&market, &U256::exp10(18), &ORACLE_PRICE_SCALE).unwrap()
+    }
+
+    #[test]
+    fn borrowed_assets_round_up_like_morpho() {
+        // Exactly one unit borrowed
+        assert!(at_boundary(1_000_000));
+        // 1.5 units: one when rounded down, two as Morpho counts it
+        assert!(!at_boundary(1_500_000));
+        // Any dust above one unit already makes it liquidatable
+        assert!(!at_boundary(1_000_001));
+    }
+}
@@ -3,7 +3,7 @@ use tokio::sync::mpsc;
 use super::{
     abi_bindings::IMorphoBlue,
     morpho_watchlist::MorphoWatchList,
-    morpho_config::MorphoConfig,
+    market_registry::{MarketInfo, MarketRegistry},
     helpers
 };
 
@@ -15,30 +15,33 @@ use ethers::{
 use futures_util::{StreamExt, stream};
 use tokio::sync::watch;
 
-use crate::common::{AdminCmd, WatchList};
+use crate::{common::{AdminCmd, WatchList}, settings::LiveSettings};
 
 pub struct WatchListUpdater<M: Middleware + 'static> {
     watch_list: Arc<MorphoWatchList>,
+    markets: Arc<MarketRegistry>,
     morpho: Arc<IMorphoBlue<M>>,
-    config: Arc<MorphoConfig>,
     shutdown: watch::Receiver<bool>,
-    cmd_rx: mpsc::Receiver<AdminCmd>
+    cmd_rx: mpsc::Receiver<AdminCmd>,
+    live: watch::Receiver<LiveSettings>,
 }
 
 impl<M: Middleware + 'static> WatchListUpdater<M> {
     pub fn new(
         list: Arc<MorphoWatchList>,
+        markets: Arc<MarketRegistry>,
         morpho: Arc<IMorphoBlue<M>>,
-        config: Arc<MorphoConfig>,
         shutdown: watch::Receiver<bool>,
-        cmd_rx: mpsc::Receiver<AdminCmd>
+        cmd_rx: mpsc::Receiver<AdminCmd>,
+        live: watch::Receiver<LiveSettings>,
     ) -> Self {
         Self {
             watch_list: list,
+            markets,
             morpho,
-            config,
             shutdown,
-            cmd_rx
+            cmd_rx,
+            live,
         }
     }
 
@@ -47,10 +50,12 @@ impl<M: Middleware + 'static> WatchListUpdater<M> {
     let borrow_filter = self.morpho.borrow_filter();
     let repay_filter = self.morpho.repay_filter();
     let liquidate_filter = self.morpho.liquidate_filter();
+    let create_market_filter = self.morpho.create_market_filter();
 
     let mut borrow_stream = borrow_filter.stream().await?;
     let mut repay_stream = repay_filter.stream().await?;
     let mut liquidate_stream = liquidate_filter.stream().await?;
+    let mut create_market_stream = create_market_filter.stream_with_meta().await?;
 
     loop {
         tokio::select! {
@@ -67,6 +72,7 @@ impl<M: Middleware + 'static> WatchListUpdater<M> {
                     Some(AdminCmd::Prune) => {
                         tracing::info!("🧹 Prune command received");
                         self.prune_watchlist().await?;
+                        self.markets.refresh_selection(&self.morpho).await?;
                     }
                     Some(AdminCmd::StatusCheck) => {}
                     
@@ -77,12 +83,41 @@ impl<M: Middleware + 'static> WatchListUpdater<M> {
                 }
             }
 
+            // 🔄 Reloaded market filters
+            Ok(()) = self.live.changed() => {
+                let live = self.live.borrow_and_update().clone();
+                self.markets.set_filters(&live);
+                self.markets.refresh_selection(&self.morpho).await?;
+            }
+
+            // 🗂️ New market
+            evt = create_market_stream.next() => {
+                if let Some(Ok((f, meta))) = evt {
+                    let market_id = H256::from(f.id);
+                    let info = MarketInfo {
+                        loan_token: f.market_params.loan_token,
+                        collateral_token: f.market_params.collateral_token,
+                        oracle: f.market_params.oracle,
+                        irm: f.market_params.irm,
+                        lltv: f.market_params.lltv,
+                        created_block: meta.block_number.as_u64(),
+                    };
+
+                    if self.markets.register(market_id, info).await? {
+                        match self.markets.evaluate(market_id, &self.morpho).await {
+                            Ok(selected) => tracing::info!("🗂️ New Morpho market {:?} (selected: {})", market_id, selected),
+                            Err(e) => tracing::warn!("Failed to evaluate Morpho market {:?}: {:?}", market_id, e),
+                        }
+                    }
+                }
+            }
+
             // 📥 Borrow
             evt = borrow_stream.next() => {
                 if let Some(Ok(f)) = evt {
                     let market_id = H256::from(f.id);
 
-                    if self.config.morpho_markets.contains(&market_id) {
+                    if self.markets.contains(&market_id) {
                         self.add_borrow(f.on_behalf, market_id).await?;
                     }
                 }
@@ -93,7 +128,7 @@ impl<M: Middleware + 'static> WatchListUpdater<M> {
                 if let Some(Ok(f)) = evt {
                     let market_id = H256::from(f.id);
 
-                    if self.config.morpho_markets.contains(&market_id) {
+                    if self.markets.contains(&market_id) {
                         self.remove_if_cleared(f.on_behalf, market_id).await?;
                     }
                 }
@@ -104,7 +139,7 @@ impl<M: Middleware + 'static> WatchListUpdater<M> {
                 if let Some(Ok(f)) = evt {
                     let market_id = H256::from(f.id);
 
-                    if self.config.morpho_markets.contains(&market_id) {
+                    if self.markets.contains(&market_id) {
                         self.remove_if_cleared(f.borrower, market_id).await?;
                     }
                 }
@@ -0,0 +1,202 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use ethers::types::{Address, H256};
+use tokio::sync::watch;
+
+use crate::constants;
+
+/// Oracle-valued state of a watched position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionClass {
+    Healthy,
+    /// Liquidatable and a candidate could be built
+    Profitable,
+    /// Liquidatable, but no liquidation pays for itself
+    Unprofitable,
+    /// Collateral worth less than the debt and nothing left to liquidate profitably
+    BadDebt,
+}
+
+impl PositionClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Profitable => "profitable",
+            Self::Unprofitable => "unprofitable",
+            Self::BadDebt => "bad_debt",
+        }
+    }
+
+    /// Classes not worth re-analyzing every cycle
+    pub fn is_hopeless(&self) -> bool {
+        matches!(self, Self::Unprofitable | Self::BadDebt)
+    }
+
+    /// Class of a liquidatable position, given whether a candidate was built
+    pub fn liquidatable(profitable: bool, underwater: bool) -> Self {
+        match (profitable, underwater) {
+            (true, _) => Self::Profitable,
+            (false, true) => Self::BadDebt,
+            (false, false) => Self::Unprofitable,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionSnapshot {
+    pub protocol: &'static str,
+    /// Aave pool address or Morpho market id
+    pub market: H256,
+    pub borrower: Address,
+    pub class: PositionClass,
+    pub collateral_value: f64,
+    pub debt_value: f64,
+    pub value_unit: String,
+}
+
+/// Classifies watched positions and keeps their history in SQLite.
+///
+/// Only class changes are written, so the table reads as a timeline per
+/// position. Hopeless positions are skipped by the liquidators until
+/// `HOPELESS_RECHECK_SECS` has passed or the position changes on-chain.
+pub struct PositionClassifier {
+    db_pool: sqlx::SqlitePool,
+    chain_id: u64,
+    latest: DashMap<(&'static str, H256, Address), (PositionClass, u64)>,
+}
+
+impl PositionClassifier {
+    pub fn new(db_pool: sqlx::SqlitePool, chain_id: u64) -> Self {
+        Self {
+            db_pool,
+            chain_id,
+            latest: DashMap::new(),
+        }
+    }
+
+    pub async fn record(&self, snapshot: PositionSnapshot) {
+        let key = (snapshot.protocol, snapshot.market, snapshot.borrower);
+        let now = now();
+
+        let changed = self
+            .latest
+            .insert(key, (snapshot.class, now))
+            .is_none_or(|(class, _)| class != snapshot.class);
+
+        if !changed {
+            return;
+        }
+
+        if snapshot.class == PositionClass::BadDebt {
+            tracing::warn!(
+                "🕳️ {} bad debt: {:?} in {:?} ({} collateral vs {} debt, {})",
+                snapshot.protocol,
+                snapshot.borrower,
+                snapshot.market,
+                snapshot.collateral_value,
+                snapshot.debt_value,
+                snapshot.value_unit
+            );
+        }
+
+        if let Err(e) = self.save(&snapshot, now).await {
+            tracing::error!("Failed to store position class: {:?}", e);
+        }
+    }
+
+    /// Whether the position was recently found hopeless
+    pub fn is_hopeless(&self, protocol: &'static str, market: H256, borrower: Address) -> bool {
+        self.latest
+            .get(&(protocol, market, borrower))
+            .is_some_and(|entry| {
+                let (class, at) = *entry;
+                class.is_hopeless() && now() < at + constants::HOPELESS_RECHECK_SECS
+            })
+    }
+
+    async fn save(&self, snapshot: &PositionSnapshot, timestamp: u64) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO position_classes (
+                protocol, market, borrower, class,
+                collateral_value, debt_value, value_unit, timestamp, chain_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(snapshot.protocol)
+        .bind(format!("{:?}", snapshot.market))
+        .bind(format!("{:?}", snapshot.borrower))
+        .bind(snapshot.class.as_str())
+        .bind(snapshot.collateral_value)
+        .bind(snapshot.debt_value)
+        .bind(&snapshot.value_unit)
+        .bind(timestamp as i64)
+        .bind(self.chain_id as i64)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Periodically log outstanding bad debt per protocol
+    pub async fn start(&self, mut shutdown: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("🕳️ Bad debt reporter started");
+        let mut ticker = tokio::time::interval(Duration::from_secs(constants::BAD_DEBT_REPORT_SECS));
+
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => {
+                    tracing::info!("🛑 Bad debt reporter shutting down");
+                    break;
+                }
+
+                _ = ticker.tick() => {
+                    if let Err(e) = self.report().await {
+                        tracing::error!("Bad debt report failed: {:?}", e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn report(&self) -> anyhow::Result<()> {
+        let rows: Vec<(String, String, i64, f64)> = sqlx::query_as(
+            r#"
+            SELECT protocol, value_unit, COUNT(*), SUM(shortfall)
+            FROM bad_debt
+            WHERE chain_id = ?
+            GROUP BY protocol, value_unit
+            "#
+        )
+        .bind(self.chain_id as i64)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        if rows.is_empty() {
+            tracing::info!("🕳️ No bad debt among watched positions on chain {}", self.chain_id);
+        }
+
+        for (protocol, unit, positions, shortfall) in rows {
+            tracing::info!(
+                "🕳️ {} bad debt on chain {}: {} positions, {:.2} shortfall ({})",
+                protocol,
+                self.chain_id,
+                positions,
+                shortfall,
+                unit
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
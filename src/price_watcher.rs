@@ -0,0 +1,127 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use ethers::{
+    providers::{Middleware, PubsubClient},
+    types::{Address, Filter},
+};
+use futures_util::StreamExt;
+use tokio::sync::{mpsc::Sender, watch};
+
+use crate::{common::Liquidator, constants};
+
+/// Emitted by Chainlink aggregators on every new round
+const ANSWER_UPDATED: &str = "AnswerUpdated(int256,uint256,uint256)";
+
+/// Streams price feed updates so the executor can re-check the affected
+/// positions without waiting for the next cycle.
+///
+/// The feed set is collected from the liquidators and refreshed every
+/// `PRICE_FEED_REFRESH_SECS`, since it follows the watchlists.
+pub struct PriceWatcher<M> {
+    client: Arc<M>,
+    liquidators: Vec<Arc<dyn Liquidator>>,
+    tx: Sender<(u64, Address)>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl<M> PriceWatcher<M>
+where
+    M: Middleware + 'static,
+    <M as Middleware>::Provider: PubsubClient,
+{
+    pub fn new(
+        client: Arc<M>,
+        liquidators: Vec<Arc<dyn Liquidator>>,
+        tx: Sender<(u64, Address)>,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            client,
+            liquidators,
+            tx,
+            shutdown,
+        }
+    }
+
+    pub async fn start(mut self) -> anyhow::Result<()> {
+        tracing::info!("📈 Price watcher task initialized");
+
+        let refresh_every = Duration::from_secs(constants::PRICE_FEED_REFRESH_SECS);
+
+        loop {
+            if *self.shutdown.borrow() {
+                break;
+            }
+
+            let feeds = self.collect_feeds().await;
+
+            if feeds.is_empty() {
+                tracing::debug!("No price feeds to watch yet");
+                tokio::select! {
+                    _ = self.shutdown.changed() => return Ok(()),
+                    _ = tokio::time::sleep(refresh_every) => continue,
+                }
+            }
+
+            let filter = Filter::new()
+                .address(feeds.iter().copied().collect::<Vec<_>>())
+                .event(ANSWER_UPDATED);
+
+            let mut stream = match self.client.subscribe_logs(&filter).await {
+                Ok(s) => {
+                    tracing::info!("✅ Watching {} price feeds", feeds.len());
+                    s
+                }
+                Err(e) => {
+                    tracing::error!("❌ Failed to subscribe to price feeds: {}. Retrying in 5s...", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            let refresh = tokio::time::sleep(refresh_every);
+            tokio::pin!(refresh);
+
+            loop {
+                tokio::select! {
+                    _ = self.shutdown.changed() => {
+                        tracing::info!("🛑 Price watcher shutting down");
+                        return Ok(());
+                    }
+
+                    // Watchlists moved on, re-collect the feed set
+                    _ = &mut refresh => break,
+
+                    maybe_log = stream.next() => {
+                        match maybe_log {
+                            Some(log) => {
+                                let block_number = log.block_number.map(|b| b.as_u64()).unwrap_or_default();
+                                tracing::debug!("📈 Price update on {:?} at block {}", log.address, block_number);
+                                let _ = self.tx.send((block_number, log.address)).await;
+                            }
+                            None => {
+                                tracing::warn!("⚠️ Price feed stream ended. Reconnecting...");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn collect_feeds(&self) -> HashSet<Address> {
+        let mut feeds = HashSet::new();
+
+        for liquidator in self.liquidators.iter() {
+            match liquidator.price_feeds().await {
+                Ok(f) => feeds.extend(f),
+                Err(e) => tracing::warn!("Failed to collect price feeds: {:?}", e),
+            }
+        }
+
+        feeds
+    }
+}
@@ -14,8 +14,8 @@ use ethers::{
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::{
+    chain_profile::ChainProfile,
     common::{self, abi_bindings::IFlashLiquidator},
-    constants,
 };
 
 use sqlx::Row;
@@ -31,6 +31,7 @@ pub struct ProfitDistributor<M: Middleware + 'static> {
     contract: Arc<IFlashLiquidator<M>>,
     running: AtomicBool,
     pool: sqlx::Pool<sqlx::Sqlite>,
+    chain: Arc<ChainProfile>,
 }
 
 impl<M: Middleware + 'static> ProfitDistributor<M> {
@@ -40,19 +41,22 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
         client: Arc<M>,
         contract: Arc<IFlashLiquidator<M>>,
         pool: sqlx::Pool<sqlx::Sqlite>,
+        chain: Arc<ChainProfile>,
     ) -> Self {
         Self {
             client,
             contract,
             running: AtomicBool::new(false),
             pool,
+            chain,
         }
     }
 
     /// Start weekly cron job
     pub async fn start(self: Arc<Self>) -> Result<()> {
-        tracing::info!("📅 ProfitDistributor started for weekly execution...");
+        tracing::info!("📅 ProfitDistributor started for weekly execution on {}...", self.chain.name);
         let sched = JobScheduler::new().await?;
+        let chain = self.chain.name.clone();
 
         // Every Sunday at 02:00 UTC
         let job = Job::new_async("0 0 2 * * Sun", move |_uuid, _l| {
@@ -79,7 +83,7 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
         sched.add(job).await?;
         sched.start().await?;
 
-        tracing::info!("📅 ProfitDistributor scheduled (Sunday 02:00 UTC)");
+        tracing::info!("📅 ProfitDistributor scheduled on {} (Sunday 02:00 UTC)", chain);
 
         Ok(())
     }
@@ -101,21 +105,21 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
     async fn ensure_gas_balance(&self) -> Result<()> {
         let gas_balance = self
             .client
-            .get_balance(constants::WALLET.address(), None)
+            .get_balance(self.chain.wallet().address(), None)
             .await?;
 
-        if gas_balance >= *constants::GAS_THRESHOLD {
+        if gas_balance >= self.chain.gas_threshold {
             return Ok(());
         }
 
         tracing::info!(
             "⛽ Gas balance {} below threshold {}. Refilling...",
             format_ether(gas_balance),
-            format_ether(*constants::GAS_THRESHOLD)
+            format_ether(self.chain.gas_threshold)
         );
 
-        let accumulated_wpol = self.contract.accumulated_profits(*constants::WPOL).call().await?;
-        let refuel_amt = constants::REFUEL_AMT.saturating_sub(gas_balance);
+        let accumulated_wpol = self.contract.accumulated_profits(self.chain.tokens.wrapped_native).call().await?;
+        let refuel_amt = self.chain.refuel_amount.saturating_sub(gas_balance);
 
         if accumulated_wpol < refuel_amt {
         tracing::warn!(
@@ -171,7 +175,7 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
                 continue;
             }
 
-            if asset == *constants::WPOL {
+            if asset == self.chain.tokens.wrapped_native {
                 // Skip WPOL since it's used for gas
                 tracing::info!(
                     "💰 Skipping WPOL profit of {} (used for gas)",
@@ -214,14 +218,17 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
     async fn discover_active_assets(&self) -> Result<HashSet<Address>> {
         let mut assets  = HashSet::new();
 
-        assets.extend(constants::PROFIT_DIST_ASSETS.iter().cloned());
+        assets.extend(self.chain.tokens.profit_assets.iter().cloned());
 
         let sql = "
-        SELECT profit_asset FROM liquidations 
+        SELECT profit_asset FROM liquidations WHERE chain_id = ?1
         UNION 
-        SELECT collateral_asset FROM liquidations";
+        SELECT collateral_asset FROM liquidations WHERE chain_id = ?1";
 
-        let rows = sqlx::query(sql).fetch_all(&self.pool).await?;
+        let rows = sqlx::query(sql)
+            .bind(self.chain.chain_id as i64)
+            .fetch_all(&self.pool)
+            .await?;
 
         for row in rows {
             if let Some(addr_str) = row.try_get::<String, _>("profit_asset").ok() {
@@ -237,8 +244,9 @@ impl<M: Middleware + 'static> ProfitDistributor<M> {
 
     /// Resolve Breet address
     fn breet_address_for(&self, asset: Address) -> Address {
-        if asset == *constants::USDC || asset == *constants::USDT {
-            *constants::BREET
+        let tokens = &self.chain.tokens;
+        if asset == tokens.usdc || asset == tokens.usdt {
+            self.chain.breet.unwrap_or_default()
         } else {
             Address::zero()
         }
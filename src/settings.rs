@@ -0,0 +1,286 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    env,
+    fmt::{Debug, Display},
+    fs,
+    hash::Hash,
+    path::PathBuf,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, bail, Context};
+use ethers::types::{Address, H256};
+use serde::Deserialize;
+
+use crate::{
+    chain_profile::{AaveDeployment, ChainTokens, CompoundDeployment, MorphoDeployment},
+    constants,
+};
+
+/// Startup configuration: built-in defaults, then the TOML file at
+/// `CONFIG_PATH`, then environment variables
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub sled_path: String,
+    pub database_url: String,
+    pub tuning: Tuning,
+    /// `[chains.<name>]` tables, `polygon` replaces the built-in profile
+    pub chains: BTreeMap<String, ChainSection>,
+}
+
+/// Tunables shared by every chain, reloaded without a restart
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tuning {
+    /// Protocols the executor runs; disabled engines keep their watchlists
+    /// up to date but do not liquidate
+    pub enabled_protocols: BTreeSet<String>,
+    /// Minimum expected profit, in bps of the repaid debt
+    pub min_profit_bps: u32,
+    /// Blocks between liquidation executor runs
+    pub liq_executor_interval: u64,
+    /// Blocks between watchlist prunes
+    pub prune_interval: u64,
+    pub aave_slippage_bps: u32,
+    pub morpho_slippage_bps: u32,
+    pub compound_slippage_bps: u32,
+    /// Slippage when swapping unwound aTokens
+    pub atoken_unwind_slippage_bps: u32,
+}
+
+/// One chain of the config file. RPC endpoints usually carry an API key
+/// and are left to the env (`RPC_URL` or `<NAME>_RPC_URL`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSection {
+    pub chain_id: u64,
+    pub rpc_url: Option<String>,
+    pub rpc_url_http: Option<String>,
+    pub flash_liquidator: Address,
+    pub breet: Option<Address>,
+    /// Native balance (whole tokens) under which the keeper is refueled
+    #[serde(default = "default_gas_threshold")]
+    pub gas_threshold: u64,
+    /// Native balance (whole tokens) a refuel tops the keeper up to
+    #[serde(default = "default_refuel_amount")]
+    pub refuel_amount: u64,
+    pub tokens: ChainTokens,
+    pub aave: Option<AaveDeployment>,
+    pub morpho: Option<MorphoDeployment>,
+    pub compound: Option<CompoundDeployment>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            sled_path: "./data/sled_db".to_string(),
+            database_url: "sqlite://./data/history.db".to_string(),
+            tuning: Tuning::default(),
+            chains: BTreeMap::new(),
+        }
+    }
+}
+
+/// The part of a chain's settings that running engines pick up on reload
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveSettings {
+    pub tuning: Tuning,
+    /// Keyed by Aave pool name
+    pub aave_reserve_filters: BTreeMap<String, ReserveFilter>,
+    pub morpho_loan_tokens: HashSet<Address>,
+    pub morpho_collateral_tokens: HashSet<Address>,
+    pub morpho_markets: HashSet<H256>,
+}
+
+/// Reserve allow/deny lists of one Aave pool
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReserveFilter {
+    pub allowlist: HashSet<Address>,
+    pub denylist: HashSet<Address>,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            enabled_protocols: PROTOCOLS.iter().map(|p| p.to_string()).collect(),
+            min_profit_bps: 0,
+            liq_executor_interval: 10,
+            prune_interval: 50,
+            aave_slippage_bps: 30,
+            morpho_slippage_bps: 30,
+            compound_slippage_bps: 30,
+            atoken_unwind_slippage_bps: 50,
+        }
+    }
+}
+
+const PROTOCOLS: [&str; 3] = ["aave", "morpho", "compound"];
+
+fn default_gas_threshold() -> u64 {
+    constants::GAS_THRESHOLD
+}
+
+fn default_refuel_amount() -> u64 {
+    constants::REFUEL_AMOUNT
+}
+
+impl Settings {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path();
+
+        let mut settings = if path.exists() {
+            let raw = fs::read_to_string(&path).with_context(|| format!("failed to read {:?}", path))?;
+            let settings: Settings = toml::from_str(&raw).with_context(|| format!("invalid config file {:?}", path))?;
+            tracing::info!("⚙️ Loaded config from {:?}", path);
+            settings
+        } else if env::var("CONFIG_PATH").is_ok() {
+            bail!("config file {:?} not found", path);
+        } else {
+            tracing::info!("⚙️ No config file at {:?}, using built-in defaults", path);
+            Settings::default()
+        };
+
+        settings.apply_env()?;
+        settings.validate()?;
+
+        Ok(settings)
+    }
+
+    pub fn path() -> PathBuf {
+        env::var("CONFIG_PATH")
+            .unwrap_or_else(|_| constants::DEFAULT_CONFIG_PATH.to_string())
+            .into()
+    }
+
+    /// Last modification of the config file, `None` without a file
+    pub fn modified() -> Option<SystemTime> {
+        fs::metadata(Self::path()).and_then(|meta| meta.modified()).ok()
+    }
+
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        override_with(&mut self.sled_path, "SLED_PATH")?;
+        override_with(&mut self.database_url, "DATABASE_URL")?;
+        self.tuning.apply_env()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.tuning.validate()
+    }
+}
+
+impl Tuning {
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        if let Ok(raw) = env::var("ENABLED_PROTOCOLS") {
+            self.enabled_protocols = raw
+                .split(',')
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect();
+        }
+        override_with(&mut self.min_profit_bps, "MIN_PROFIT_BPS")?;
+        override_with(&mut self.liq_executor_interval, "LIQ_EXECUTOR_INTERVAL")?;
+        override_with(&mut self.prune_interval, "PRUNE_INTERVAL")?;
+        override_with(&mut self.aave_slippage_bps, "AAVE_SLIPPAGE_BPS")?;
+        override_with(&mut self.morpho_slippage_bps, "MORPHO_SLIPPAGE_BPS")?;
+        override_with(&mut self.compound_slippage_bps, "COMPOUND_SLIPPAGE_BPS")?;
+        override_with(&mut self.atoken_unwind_slippage_bps, "ATOKEN_UNWIND_SLIPPAGE_BPS")
+    }
+
+    pub fn is_enabled(&self, protocol: &str) -> bool {
+        self.enabled_protocols.contains(protocol)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(unknown) = self.enabled_protocols.iter().find(|p| !PROTOCOLS.contains(&p.as_str())) {
+            bail!("unknown protocol {} in enabled_protocols, expected one of {:?}", unknown, PROTOCOLS);
+        }
+
+        if self.liq_executor_interval == 0 || self.prune_interval == 0 {
+            bail!("liq_executor_interval and prune_interval must be at least one block");
+        }
+
+        let slippages = [
+            ("aave_slippage_bps", self.aave_slippage_bps),
+            ("morpho_slippage_bps", self.morpho_slippage_bps),
+            ("compound_slippage_bps", self.compound_slippage_bps),
+            ("atoken_unwind_slippage_bps", self.atoken_unwind_slippage_bps),
+            ("min_profit_bps", self.min_profit_bps),
+        ];
+        for (key, bps) in slippages {
+            if bps >= 10_000 {
+                bail!("{} must be below 10000, got {}", key, bps);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LiveSettings {
+    /// One `key: old → new` entry per changed setting
+    pub fn diff(&self, next: &LiveSettings) -> Vec<String> {
+        let (old, new) = (&self.tuning, &next.tuning);
+        let mut changes = Vec::new();
+
+        changed(&mut changes, "enabled_protocols", &old.enabled_protocols, &new.enabled_protocols);
+        changed(&mut changes, "min_profit_bps", &old.min_profit_bps, &new.min_profit_bps);
+        changed(&mut changes, "liq_executor_interval", &old.liq_executor_interval, &new.liq_executor_interval);
+        changed(&mut changes, "prune_interval", &old.prune_interval, &new.prune_interval);
+        changed(&mut changes, "aave_slippage_bps", &old.aave_slippage_bps, &new.aave_slippage_bps);
+        changed(&mut changes, "morpho_slippage_bps", &old.morpho_slippage_bps, &new.morpho_slippage_bps);
+        changed(&mut changes, "compound_slippage_bps", &old.compound_slippage_bps, &new.compound_slippage_bps);
+        changed(&mut changes, "atoken_unwind_slippage_bps", &old.atoken_unwind_slippage_bps, &new.atoken_unwind_slippage_bps);
+
+        let pools: BTreeSet<&String> = self.aave_reserve_filters.keys().chain(next.aave_reserve_filters.keys()).collect();
+        for pool in pools {
+            let old = self.aave_reserve_filters.get(pool).cloned().unwrap_or_default();
+            let new = next.aave_reserve_filters.get(pool).cloned().unwrap_or_default();
+            let prefix = if pool == constants::AAVE_DEFAULT_POOL {
+                pool.clone()
+            } else {
+                format!("aave.{}", pool)
+            };
+            set_changed(&mut changes, &format!("{}.reserve_allowlist", prefix), &old.allowlist, &new.allowlist);
+            set_changed(&mut changes, &format!("{}.reserve_denylist", prefix), &old.denylist, &new.denylist);
+        }
+        set_changed(&mut changes, "morpho.loan_tokens", &self.morpho_loan_tokens, &next.morpho_loan_tokens);
+        set_changed(&mut changes, "morpho.collateral_tokens", &self.morpho_collateral_tokens, &next.morpho_collateral_tokens);
+        set_changed(&mut changes, "morpho.markets", &self.morpho_markets, &next.morpho_markets);
+
+        changes
+    }
+}
+
+fn changed<T: PartialEq + Debug>(changes: &mut Vec<String>, key: &str, old: &T, new: &T) {
+    if old != new {
+        changes.push(format!("{}: {:?} → {:?}", key, old, new));
+    }
+}
+
+fn set_changed<T: Eq + Hash + Debug>(changes: &mut Vec<String>, key: &str, old: &HashSet<T>, new: &HashSet<T>) {
+    let added: Vec<&T> = new.difference(old).collect();
+    let removed: Vec<&T> = old.difference(new).collect();
+
+    if !added.is_empty() || !removed.is_empty() {
+        changes.push(format!("{}: +{:?} -{:?}", key, added, removed));
+    }
+}
+
+/// Replace `value` with env var `var` when it is set
+pub fn override_with<T>(value: &mut T, var: &str) -> anyhow::Result<()>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(raw) = env::var(var) {
+        *value = raw
+            .trim()
+            .parse()
+            .map_err(|e| anyhow!("invalid value in {}: {}", var, e))?;
+    }
+
+    Ok(())
+}
+
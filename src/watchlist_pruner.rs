@@ -1,37 +1,33 @@
 use tokio::sync::{mpsc, broadcast, watch};
 use tokio::sync::broadcast::error::RecvError;
-use crate::common::AdminCmd;
+use crate::{common::AdminCmd, settings::LiveSettings};
 
 pub struct WatchListPruner {
-    aave_cmd: mpsc::Sender<AdminCmd>,
-    morpho_cmd: mpsc::Sender<AdminCmd>,
-    comet_cmd: mpsc::Sender<AdminCmd>,
+    /// Admin channel of every engine, by engine name
+    engine_cmds: Vec<(String, mpsc::Sender<AdminCmd>)>,
     block_rx: broadcast::Receiver<u64>,
     shutdown: watch::Receiver<bool>,
-    interval: u64,
+    /// Prune interval, read on every block
+    live: watch::Receiver<LiveSettings>,
 }
 
 impl WatchListPruner {
     pub fn new(
-        aave_cmd: mpsc::Sender<AdminCmd>,
-        morpho_cmd: mpsc::Sender<AdminCmd>,
-        comet_cmd: mpsc::Sender<AdminCmd>,
+        engine_cmds: Vec<(String, mpsc::Sender<AdminCmd>)>,
         block_rx: broadcast::Receiver<u64>,
         shutdown: watch::Receiver<bool>,
-        interval: u64,
+        live: watch::Receiver<LiveSettings>,
     ) -> Self {
         Self {
-            aave_cmd,
-            morpho_cmd,
-            comet_cmd,
+            engine_cmds,
             block_rx,
             shutdown,
-            interval,
+            live,
         }
     }
 
     pub async fn start(&mut self) -> anyhow::Result<()> {
-        tracing::info!("📡 WatchListPruner started (every {} blocks)", self.interval);
+        tracing::info!("📡 WatchListPruner started (every {} blocks)", self.live.borrow().tuning.prune_interval);
         loop {
             tokio::select! {
                 _ = self.shutdown.changed() => {
@@ -42,10 +38,8 @@ impl WatchListPruner {
                 evt = self.block_rx.recv() => {
                     match evt {
                         Ok(block_number) => {
-                            if block_number % self.interval == 0 {
-                                let aave = self.aave_cmd.clone();
-                                let morpho = self.morpho_cmd.clone();
-                                let comet = self.comet_cmd.clone();
+                            if block_number.is_multiple_of(self.live.borrow().tuning.prune_interval) {
+                                let engines = self.engine_cmds.clone();
 
                                 tokio::spawn(async move {
                                     tracing::info!(
@@ -53,15 +47,10 @@ impl WatchListPruner {
                                         block_number
                                     );
 
-                                    if let Err(e) = aave.send(AdminCmd::Prune).await {
-                                        tracing::error!("Failed to send prune to Aave: {:?}", e);
-                                    }
-
-                                    if let Err(e) = morpho.send(AdminCmd::Prune).await {
-                                        tracing::error!("Failed to send prune to Morpho: {:?}", e);
-                                    }
-                                    if let Err(e) = comet.send(AdminCmd::Prune).await {
-                                        tracing::error!("Failed to send prune to Comet: {:?}", e);
+                                    for (name, engine) in engines.iter() {
+                                        if let Err(e) = engine.send(AdminCmd::Prune).await {
+                                            tracing::error!("Failed to send prune to {}: {:?}", name, e);
+                                        }
                                     }
                                 });
                             }